@@ -0,0 +1,29 @@
+//! Lowercasing a 64-byte ASCII string in-place, where `LeanString::make_ascii_lowercase` converts
+//! a word at a time. `str::make_ascii_lowercase` is the baseline.
+//!
+//! The same unique buffer is converted in every iteration, so this measures the conversion
+//! itself rather than the first access to a cold buffer.
+
+use core::hint::black_box;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use lean_string::LeanString;
+
+const TEXT: &str = "The Quick Brown Fox Jumps Over The Lazy Dog, AND THEN IT SLEEPS.";
+
+fn ascii_lowercase(c: &mut Criterion) {
+    assert_eq!(TEXT.len(), 64);
+
+    let mut group = c.benchmark_group("make_ascii_lowercase 64 B");
+    group.throughput(Throughput::Bytes(TEXT.len() as u64));
+
+    let mut lean = LeanString::from(TEXT);
+    group.bench_function("LeanString", |b| b.iter(|| black_box(&mut lean).make_ascii_lowercase()));
+
+    let mut string = String::from(TEXT);
+    group.bench_function("str", |b| b.iter(|| black_box(&mut string).make_ascii_lowercase()));
+
+    group.finish();
+}
+
+criterion_group!(benches, ascii_lowercase);
+criterion_main!(benches);
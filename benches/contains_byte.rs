@@ -0,0 +1,31 @@
+//! Searching an ASCII byte with `LeanString::contains_ascii_byte`, which compares a word at a
+//! time for inline strings. `str::contains(char)` is the baseline.
+
+use core::hint::black_box;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use lean_string::LeanString;
+
+fn contains_byte(c: &mut Criterion) {
+    let inline = "key=value&x=1";
+    let heap = "key=value&other_key=other_value&yet_another_key=yet_another_value";
+    assert!(inline.len() <= size_of::<LeanString>() && heap.len() > size_of::<LeanString>());
+
+    let mut group = c.benchmark_group("contains_ascii_byte");
+    for (name, text) in [("inline", inline), ("heap", heap)] {
+        let lean = LeanString::from(text);
+        // found near the end, and not found
+        for byte in [b'1', b'#'] {
+            let id = format!("{name} {:?}", byte as char);
+            group.bench_with_input(BenchmarkId::new("LeanString", &id), &byte, |b, &byte| {
+                b.iter(|| black_box(&lean).contains_ascii_byte(black_box(byte)))
+            });
+            group.bench_with_input(BenchmarkId::new("str::contains", &id), &byte, |b, &byte| {
+                b.iter(|| black_box(lean.as_str()).contains(black_box(byte as char)))
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, contains_byte);
+criterion_main!(benches);
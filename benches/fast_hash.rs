@@ -0,0 +1,28 @@
+//! Hashing short keys with `LeanString::fast_hash`, against the default `SipHash` of
+//! `std::collections::HashMap` (through the `Hash` implementation of `LeanString`).
+
+use core::hash::BuildHasher;
+use core::hint::black_box;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use lean_string::LeanString;
+use std::hash::RandomState;
+
+fn fast_hash(c: &mut Criterion) {
+    let state = RandomState::new();
+
+    let mut group = c.benchmark_group("hash");
+    for key in ["8B_key!!", "a 23 byte key for a map", "a 24 byte key for a map!"] {
+        let key = LeanString::from(key);
+        let id = format!("{} B", key.len());
+        group.bench_with_input(BenchmarkId::new("fast_hash", &id), &key, |b, key| {
+            b.iter(|| black_box(key).fast_hash())
+        });
+        group.bench_with_input(BenchmarkId::new("SipHash", &id), &key, |b, key| {
+            b.iter(|| state.hash_one(black_box(key)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, fast_hash);
+criterion_main!(benches);
@@ -0,0 +1,42 @@
+//! UTF-8 validation throughput of `LeanString::from_utf8` with the `simd` feature, against
+//! `core::str::from_utf8` followed by the same copy into a `LeanString`.
+
+use core::hint::black_box;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use lean_string::LeanString;
+
+fn inputs() -> Vec<(&'static str, Vec<u8>)> {
+    let ascii = |len: usize| {
+        "The quick brown fox jumps over the lazy dog. ".repeat(len / 45 + 1)[..len].into()
+    };
+    let mut mixed: Vec<u8> = ascii(64 * 1024);
+    // a non-ASCII char in the middle, so half of the input takes the slow path
+    mixed.splice(32 * 1024..32 * 1024 + 2, "é".bytes());
+    vec![
+        ("ascii 64 B", ascii(64)),
+        ("ascii 4 KiB", ascii(4 * 1024)),
+        ("ascii 64 KiB", ascii(64 * 1024)),
+        ("mixed 64 KiB", mixed),
+    ]
+}
+
+fn from_utf8(c: &mut Criterion) {
+    let mut group = c.benchmark_group("from_utf8");
+    for (name, input) in inputs() {
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("LeanString::from_utf8", name),
+            &input,
+            |b, input| b.iter(|| LeanString::from_utf8(black_box(input))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("core::str::from_utf8", name),
+            &input,
+            |b, input| b.iter(|| core::str::from_utf8(black_box(input)).map(LeanString::from)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, from_utf8);
+criterion_main!(benches);
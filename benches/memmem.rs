@@ -0,0 +1,48 @@
+//! Searching and replacing in a 1 MiB haystack, where the `memchr` feature routes multi-byte
+//! patterns through `memchr::memmem`. The `str` methods are the baseline.
+
+use core::hint::black_box;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use lean_string::LeanString;
+
+fn haystack() -> String {
+    let line = "lorem ipsum dolor sit amet, consectetur adipiscing elit; ";
+    let mut haystack = line.repeat((1 << 20) / line.len() + 1);
+    haystack.truncate(1 << 20);
+    haystack
+}
+
+fn memmem(c: &mut Criterion) {
+    let string = haystack();
+    let lean = LeanString::from(string.as_str());
+
+    let mut group = c.benchmark_group("1 MiB haystack");
+    group.throughput(Throughput::Bytes(string.len() as u64));
+
+    // not found, so the whole haystack is scanned
+    group.bench_function("contains_substr", |b| {
+        b.iter(|| black_box(&lean).contains_substr(black_box("amet, lorem")))
+    });
+    group.bench_function("str::contains", |b| {
+        b.iter(|| black_box(string.as_str()).contains(black_box("amet, lorem")))
+    });
+
+    group.bench_function("count_matches", |b| {
+        b.iter(|| black_box(&lean).count_matches(black_box("adipiscing")))
+    });
+    group.bench_function("str::matches().count()", |b| {
+        b.iter(|| black_box(string.as_str()).matches(black_box("adipiscing")).count())
+    });
+
+    group.bench_function("replace_lean", |b| {
+        b.iter(|| black_box(&lean).replace_lean(black_box("dolor"), black_box("DOLOR")))
+    });
+    group.bench_function("str::replace", |b| {
+        b.iter(|| black_box(string.as_str()).replace(black_box("dolor"), black_box("DOLOR")))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, memmem);
+criterion_main!(benches);
@@ -0,0 +1,50 @@
+//! Pushing ASCII chars one by one onto a short inline string until the inline buffer is full,
+//! where `LeanString::push` skips `reserve`. A `String` with enough capacity is the baseline.
+
+use core::hint::black_box;
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use lean_string::LeanString;
+
+fn push_inline(c: &mut Criterion) {
+    let start = "id";
+    let chars: Vec<char> = ('a'..='z').take(size_of::<LeanString>() - start.len()).collect();
+
+    let mut full = LeanString::from(start);
+    chars.iter().for_each(|&ch| full.push(ch));
+    assert!(!full.is_heap_allocated());
+
+    let mut group = c.benchmark_group("push ASCII chars onto an inline string");
+
+    group.bench_function("LeanString", |b| {
+        b.iter_batched_ref(
+            || LeanString::from(start),
+            |s| {
+                for &ch in black_box(&chars) {
+                    s.push(ch);
+                }
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    group.bench_function("String", |b| {
+        b.iter_batched_ref(
+            || {
+                let mut s = String::with_capacity(size_of::<LeanString>());
+                s.push_str(start);
+                s
+            },
+            |s| {
+                for &ch in black_box(&chars) {
+                    s.push(ch);
+                }
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, push_inline);
+criterion_main!(benches);
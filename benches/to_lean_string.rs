@@ -0,0 +1,27 @@
+//! Converting a string slice with `ToLeanString`, where `str` has a dedicated impl which copies
+//! the slice directly. The previous path, formatting `&str` through `Display` by the blanket
+//! impl, is the baseline.
+
+use core::hint::black_box;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use lean_string::{LeanString, ToLeanString};
+
+fn to_lean_string(c: &mut Criterion) {
+    let inline = "hello";
+    let heap = "hello, a string which is too long to be inline";
+    assert!(inline.len() <= size_of::<LeanString>() && heap.len() > size_of::<LeanString>());
+
+    let mut group = c.benchmark_group("to_lean_string");
+    for (name, text) in [("inline", inline), ("heap", heap)] {
+        group.bench_with_input(BenchmarkId::new("str", name), text, |b, text| {
+            b.iter(|| black_box(text).to_lean_string())
+        });
+        group.bench_with_input(BenchmarkId::new("Display", name), &text, |b, text| {
+            b.iter(|| <&str as ToLeanString>::to_lean_string(black_box(text)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, to_lean_string);
+criterion_main!(benches);
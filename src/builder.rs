@@ -0,0 +1,149 @@
+use crate::{LeanString, ReserveError, UnwrapWithMsg};
+
+/// A builder for assembling a [`LeanString`] from pieces.
+///
+/// [`LeanStringBuilder`] reserves the capacity once when it is created, and appends pieces without
+/// checking the uniqueness of the buffer as long as they fit in the reserved capacity. If a piece
+/// doesn't fit, the buffer grows the same way as [`LeanString::push_str()`].
+///
+/// # Examples
+///
+/// ```
+/// # use lean_string::LeanStringBuilder;
+/// let mut builder = LeanStringBuilder::with_capacity(64);
+/// for word in ["The", "quick", "brown", "fox", "jumps"] {
+///     builder.push_str(word);
+///     builder.push(' ');
+/// }
+///
+/// let s = builder.finish_and_shrink();
+/// assert_eq!(s, "The quick brown fox jumps ");
+/// assert_eq!(s.capacity(), s.len());
+/// ```
+pub struct LeanStringBuilder(
+    // INVARIANT: The buffer is not StaticBuffer, and it is unique if it is HeapBuffer.
+    LeanString,
+);
+
+impl LeanStringBuilder {
+    /// Creates a new [`LeanStringBuilder`] with at least `capacity` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory, or the `capacity` is too large. If you want to
+    /// handle such a problem manually, use [`LeanStringBuilder::try_with_capacity()`].
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        LeanStringBuilder::try_with_capacity(capacity).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanStringBuilder::with_capacity()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, or the `capacity` is too large, but
+    /// return an [`ReserveError`]. Otherwise it behaves the same as
+    /// [`LeanStringBuilder::with_capacity()`].
+    #[inline]
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, ReserveError> {
+        // `with_capacity` creates an InlineBuffer or a unique HeapBuffer.
+        LeanString::try_with_capacity(capacity).map(LeanStringBuilder)
+    }
+
+    /// Returns the length of the string built so far, in bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if nothing has been appended yet.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the capacity of the buffer, in bytes.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.0.capacity()
+    }
+
+    /// Returns the string built so far.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    /// Appends the given string slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory when growing the buffer. If you want to handle such
+    /// a problem manually, use [`LeanStringBuilder::try_push_str()`].
+    #[inline]
+    pub fn push_str(&mut self, string: &str) {
+        self.try_push_str(string).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanStringBuilder::push_str()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, or the `capacity` is too large, but
+    /// return an [`ReserveError`]. Otherwise it behaves the same as
+    /// [`LeanStringBuilder::push_str()`].
+    #[inline]
+    pub fn try_push_str(&mut self, string: &str) -> Result<(), ReserveError> {
+        let repr = &mut self.0 .0;
+        if string.len() <= repr.capacity() - repr.len() {
+            // SAFETY:
+            // - From the invariant, the buffer is modifiable.
+            // - We just checked that `string` fits in the capacity.
+            unsafe { repr.push_str_unchecked(string) };
+            Ok(())
+        } else {
+            // `push_str` keeps the invariant because it makes the buffer modifiable.
+            repr.push_str(string)
+        }
+    }
+
+    /// Appends the given [`char`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory when growing the buffer. If you want to handle such
+    /// a problem manually, use [`LeanStringBuilder::try_push()`].
+    #[inline]
+    pub fn push(&mut self, ch: char) {
+        self.try_push(ch).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanStringBuilder::push()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, or the `capacity` is too large, but
+    /// return an [`ReserveError`]. Otherwise it behaves the same as [`LeanStringBuilder::push()`].
+    #[inline]
+    pub fn try_push(&mut self, ch: char) -> Result<(), ReserveError> {
+        self.try_push_str(ch.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Consumes the builder and returns the built [`LeanString`], keeping the reserved capacity.
+    #[inline]
+    pub fn finish(self) -> LeanString {
+        self.0
+    }
+
+    /// Consumes the builder and returns the built [`LeanString`], shrinking its capacity to fit.
+    ///
+    /// Since the buffer is unique, shrinking never clones it, but reallocation may still fail. In
+    /// that case, the [`LeanString`] is returned without shrinking.
+    #[inline]
+    pub fn finish_and_shrink(self) -> LeanString {
+        let mut s = self.0;
+        let _ = s.try_shrink_to_fit();
+        s
+    }
+}
+
+impl Default for LeanStringBuilder {
+    #[inline]
+    fn default() -> Self {
+        LeanStringBuilder(LeanString::new())
+    }
+}
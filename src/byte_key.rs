@@ -0,0 +1,107 @@
+use crate::LeanString;
+use core::{
+    borrow::Borrow,
+    cmp, fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+};
+
+/// A [`LeanString`] wrapper which compares and hashes as its UTF-8 bytes.
+///
+/// This can be used as a key of a `HashMap` or `BTreeMap` which is looked up by `&[u8]`, e.g.
+/// with bytes read from a network or a file, without validating them as UTF-8 first.
+/// [`Borrow<[u8]>`][Borrow] is implemented, and [`Hash`], [`Eq`] and [`Ord`] are consistent with
+/// those of `[u8]`.
+///
+/// [`LeanString`] itself doesn't implement `Borrow<[u8]>`, because its [`Hash`] is the same as
+/// [`prim@str`], which differs from `[u8]`.
+///
+/// # Examples
+///
+/// ```
+/// # use lean_string::{ByteKey, LeanString};
+/// use std::collections::HashMap;
+///
+/// let mut map = HashMap::new();
+/// map.insert(ByteKey(LeanString::from("apple")), 1);
+///
+/// assert_eq!(map.get(b"apple".as_slice()), Some(&1));
+/// assert_eq!(map.get(b"cherry".as_slice()), None);
+/// ```
+#[derive(Clone, Default)]
+pub struct ByteKey(pub LeanString);
+
+impl ByteKey {
+    /// Unwraps the inner [`LeanString`].
+    #[inline]
+    pub fn into_inner(self) -> LeanString {
+        self.0
+    }
+}
+
+impl Deref for ByteKey {
+    type Target = LeanString;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<LeanString> for ByteKey {
+    #[inline]
+    fn from(value: LeanString) -> Self {
+        ByteKey(value)
+    }
+}
+
+impl Borrow<[u8]> for ByteKey {
+    #[inline]
+    fn borrow(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+impl PartialEq for ByteKey {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_bytes() == other.0.as_bytes()
+    }
+}
+
+impl Eq for ByteKey {}
+
+impl PartialOrd for ByteKey {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ByteKey {
+    #[inline]
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.0.as_bytes().cmp(other.0.as_bytes())
+    }
+}
+
+impl Hash for ByteKey {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.as_bytes().hash(state)
+    }
+}
+
+impl fmt::Debug for ByteKey {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Display for ByteKey {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
@@ -0,0 +1,108 @@
+use crate::LeanString;
+use core::{
+    cmp, fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+};
+
+/// A [`LeanString`] wrapper which compares and hashes ignoring ASCII case.
+///
+/// This can be used as a case-insensitive key of a `HashMap` or `BTreeMap`, e.g. for HTTP
+/// header names. [`Hash`], [`Eq`] and [`Ord`] are consistent with
+/// [`str::eq_ignore_ascii_case()`], and the original case is kept in the wrapped
+/// [`LeanString`].
+///
+/// # Examples
+///
+/// ```
+/// # use lean_string::{AsciiCaseInsensitive, LeanString};
+/// use std::collections::HashMap;
+///
+/// let mut headers = HashMap::new();
+/// headers.insert(AsciiCaseInsensitive(LeanString::from("Content-Type")), "text/plain");
+///
+/// let key = AsciiCaseInsensitive(LeanString::from("content-type"));
+/// assert_eq!(headers.get(&key), Some(&"text/plain"));
+///
+/// let (key, _) = headers.get_key_value(&key).unwrap();
+/// assert_eq!(key.as_str(), "Content-Type");
+/// ```
+#[derive(Clone, Default)]
+pub struct AsciiCaseInsensitive(pub LeanString);
+
+impl AsciiCaseInsensitive {
+    /// Unwraps the inner [`LeanString`].
+    #[inline]
+    pub fn into_inner(self) -> LeanString {
+        self.0
+    }
+}
+
+impl Deref for AsciiCaseInsensitive {
+    type Target = LeanString;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<LeanString> for AsciiCaseInsensitive {
+    #[inline]
+    fn from(value: LeanString) -> Self {
+        AsciiCaseInsensitive(value)
+    }
+}
+
+impl PartialEq for AsciiCaseInsensitive {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_ignore_ascii_case(&other.0)
+    }
+}
+
+impl Eq for AsciiCaseInsensitive {}
+
+impl PartialOrd for AsciiCaseInsensitive {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for AsciiCaseInsensitive {
+    #[inline]
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.0.cmp_ignore_ascii_case(&other.0)
+    }
+}
+
+impl Hash for AsciiCaseInsensitive {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Lowercase through a small buffer instead of allocating. Equal keys have the same
+        // length, so they are split into the same chunks.
+        let mut buf = [0; 64];
+        for chunk in self.0.as_bytes().chunks(buf.len()) {
+            let buf = &mut buf[..chunk.len()];
+            buf.copy_from_slice(chunk);
+            buf.make_ascii_lowercase();
+            state.write(buf);
+        }
+        // The same terminator as `str`, so that `("ab", "c")` and `("a", "bc")` differ.
+        state.write_u8(0xff);
+    }
+}
+
+impl fmt::Debug for AsciiCaseInsensitive {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Display for AsciiCaseInsensitive {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
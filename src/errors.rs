@@ -1,8 +1,17 @@
 mod reserve_error;
 pub use reserve_error::ReserveError;
 
+mod from_utf8_error;
+pub use from_utf8_error::FromUtf8Error;
+
 mod from_utf16_error;
 pub use from_utf16_error::FromUtf16Error;
 
 mod to_lean_string_error;
 pub use to_lean_string_error::ToLeanStringError;
+
+mod push_bytes_error;
+pub use push_bytes_error::PushBytesError;
+
+mod insert_error;
+pub use insert_error::InsertError;
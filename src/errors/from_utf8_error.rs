@@ -0,0 +1,12 @@
+use core::{error::Error, fmt};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FromUtf8Error;
+
+impl Error for FromUtf8Error {}
+
+impl fmt::Display for FromUtf8Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid utf-8 sequence")
+    }
+}
@@ -0,0 +1,36 @@
+use core::{error::Error, fmt};
+
+use super::ReserveError;
+
+/// A possible error value when inserting a string into a [`LeanString`] with
+/// [`LeanString::checked_insert_str()`].
+///
+/// [`LeanString`]: crate::LeanString
+/// [`LeanString::checked_insert_str()`]: crate::LeanString::checked_insert_str
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InsertError {
+    /// The index does not lie on a [`char`] boundary.
+    NotCharBoundary,
+    /// The index is larger than the length.
+    OutOfBounds,
+    /// Allocating memory for the inserted string failed.
+    Reserve(ReserveError),
+}
+
+impl Error for InsertError {}
+
+impl fmt::Display for InsertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InsertError::NotCharBoundary => f.write_str("index is not a char boundary"),
+            InsertError::OutOfBounds => f.write_str("index out of bounds"),
+            InsertError::Reserve(e) => e.fmt(f),
+        }
+    }
+}
+
+impl From<ReserveError> for InsertError {
+    fn from(value: ReserveError) -> Self {
+        InsertError::Reserve(value)
+    }
+}
@@ -0,0 +1,39 @@
+use core::{error::Error, fmt, str::Utf8Error};
+
+use super::ReserveError;
+
+/// A possible error value when appending bytes to a [`LeanString`] with
+/// [`LeanString::push_bytes_checked()`].
+///
+/// [`LeanString`]: crate::LeanString
+/// [`LeanString::push_bytes_checked()`]: crate::LeanString::push_bytes_checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushBytesError {
+    /// The bytes are not valid UTF-8.
+    Utf8(Utf8Error),
+    /// Allocating memory for the bytes failed.
+    Reserve(ReserveError),
+}
+
+impl Error for PushBytesError {}
+
+impl fmt::Display for PushBytesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PushBytesError::Utf8(e) => e.fmt(f),
+            PushBytesError::Reserve(e) => e.fmt(f),
+        }
+    }
+}
+
+impl From<Utf8Error> for PushBytesError {
+    fn from(value: Utf8Error) -> Self {
+        PushBytesError::Utf8(value)
+    }
+}
+
+impl From<ReserveError> for PushBytesError {
+    fn from(value: ReserveError) -> Self {
+        PushBytesError::Reserve(value)
+    }
+}
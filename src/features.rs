@@ -1,5 +1,29 @@
+#[cfg(feature = "alloc-hooks")]
+pub(crate) mod alloc_hooks;
+
 #[cfg(feature = "arbitrary")]
 mod arbitrary;
 
+#[cfg(feature = "compact_str")]
+mod compact_str;
+
+#[cfg(feature = "fast_hash")]
+mod fast_hash;
+
+#[cfg(feature = "memchr")]
+pub(crate) mod memchr;
+
+#[cfg(feature = "nightly")]
+mod pattern;
+
 #[cfg(feature = "serde")]
 mod serde;
+
+#[cfg(feature = "simd")]
+pub(crate) mod simd;
+
+#[cfg(feature = "unicode-segmentation")]
+mod unicode_segmentation;
+
+#[cfg(feature = "unicode-width")]
+mod unicode_width;
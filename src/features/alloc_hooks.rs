@@ -0,0 +1,83 @@
+//! A global observer of the heap allocations of [`LeanString`](crate::LeanString).
+
+use core::{
+    mem, ptr,
+    sync::atomic::{AtomicPtr, Ordering::*},
+};
+
+/// An event of a heap buffer of [`LeanString`](crate::LeanString), reported to the observer set
+/// by [`set_alloc_observer()`].
+///
+/// The capacities are in bytes, and don't include the header of the buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum AllocEvent {
+    /// A new heap buffer was allocated.
+    Allocate {
+        /// The capacity of the new buffer.
+        capacity: usize,
+    },
+    /// A heap buffer was resized.
+    Reallocate {
+        /// The capacity before resizing.
+        old_capacity: usize,
+        /// The capacity after resizing.
+        new_capacity: usize,
+    },
+    /// A heap buffer was freed.
+    Deallocate {
+        /// The capacity of the freed buffer.
+        capacity: usize,
+    },
+}
+
+/// The current observer, or null if it is not set.
+static OBSERVER: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Sets the global observer which is called on every heap allocation, reallocation and
+/// deallocation of [`LeanString`](crate::LeanString).
+///
+/// This replaces the previous observer. The observer is called on the thread that performs the
+/// allocation, right after it (or right before the deallocation), so it should be cheap and it
+/// must not panic. This is useful to find unexpected heap churn without a full profiler.
+///
+/// # Examples
+///
+/// ```
+/// # use lean_string::{set_alloc_observer, clear_alloc_observer, AllocEvent, LeanString};
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+///
+/// set_alloc_observer(|event| {
+///     if let AllocEvent::Allocate { .. } = event {
+///         ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+///     }
+/// });
+///
+/// let s = LeanString::from("a long string which is allocated on the heap");
+/// assert!(ALLOCATIONS.load(Ordering::Relaxed) >= 1);
+/// # drop(s);
+/// clear_alloc_observer();
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc-hooks")))]
+pub fn set_alloc_observer(observer: fn(AllocEvent)) {
+    OBSERVER.store(observer as *mut (), Release);
+}
+
+/// Removes the global observer set by [`set_alloc_observer()`].
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc-hooks")))]
+pub fn clear_alloc_observer() {
+    OBSERVER.store(ptr::null_mut(), Release);
+}
+
+/// Reports `event` to the current observer, if any.
+#[inline]
+pub(crate) fn notify(event: AllocEvent) {
+    let observer = OBSERVER.load(Acquire);
+    if !observer.is_null() {
+        // SAFETY: A non-null `OBSERVER` is always stored from a `fn(AllocEvent)`.
+        let observer: fn(AllocEvent) = unsafe { mem::transmute(observer) };
+        observer(event);
+    }
+}
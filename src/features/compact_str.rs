@@ -0,0 +1,30 @@
+use crate::LeanString;
+use compact_str::CompactString;
+
+/// Copies a [`CompactString`] into a new [`LeanString`].
+///
+/// The layouts are different, so the content is always copied. Short strings are inlined on both
+/// sides.
+#[cfg_attr(docsrs, doc(cfg(feature = "compact_str")))]
+impl From<CompactString> for LeanString {
+    #[inline]
+    #[track_caller]
+    fn from(value: CompactString) -> Self {
+        LeanString::from(value.as_str())
+    }
+}
+
+/// Copies a [`LeanString`] into a new [`CompactString`].
+///
+/// A static buffer of [`LeanString`] is converted to a static [`CompactString`] without copying.
+#[cfg_attr(docsrs, doc(cfg(feature = "compact_str")))]
+impl From<LeanString> for CompactString {
+    #[inline]
+    #[track_caller]
+    fn from(value: LeanString) -> Self {
+        match value.0.as_static_str() {
+            Some(text) => CompactString::const_new(text),
+            None => CompactString::new(value.as_str()),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use crate::LeanString;
+
+/// The multiplier of FxHash.
+const K: u64 = 0x517c_c1b7_2722_0a95;
+
+#[inline(always)]
+const fn mix_word(hash: u64, word: u64) -> u64 {
+    (hash.rotate_left(5) ^ word).wrapping_mul(K)
+}
+
+/// The finalizer of MurmurHash3, which spreads the entropy of the upper bits to the lower bits.
+#[inline(always)]
+const fn finalize(mut hash: u64) -> u64 {
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    hash ^ (hash >> 33)
+}
+
+/// Reads the 1 to 7 bytes of `tail` into a word, without a variable-length copy.
+///
+/// The loads overlap, but all bytes are read, and the length is already mixed into the hash, so
+/// different tails of the same length give different words.
+#[inline(always)]
+fn read_tail(tail: &[u8]) -> u64 {
+    let len = tail.len();
+    debug_assert!((1..8).contains(&len));
+    if len >= 4 {
+        let lo = u32::from_le_bytes([tail[0], tail[1], tail[2], tail[3]]);
+        let hi = u32::from_le_bytes([tail[len - 4], tail[len - 3], tail[len - 2], tail[len - 1]]);
+        lo as u64 | (hi as u64) << 32
+    } else {
+        tail[0] as u64 | (tail[len / 2] as u64) << 8 | (tail[len - 1] as u64) << 16
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "fast_hash")))]
+impl LeanString {
+    /// Returns a fast, non-cryptographic 64-bit hash of the [`LeanString`].
+    ///
+    /// The bytes are mixed 8 bytes at a time (like FxHash) and the result is finalized, so short
+    /// keys are hashed in a few instructions. This is useful for hash maps where speed matters
+    /// and DoS resistance isn't needed.
+    ///
+    /// This is unrelated to the [`Hash`](core::hash::Hash) implementation, which is consistent
+    /// with [`str`]. The result is the same on every platform, but may change between versions of
+    /// this crate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let a = LeanString::from("key");
+    /// let b = LeanString::from_static_str("key");
+    /// assert_eq!(a.fast_hash(), b.fast_hash());
+    /// assert_ne!(a.fast_hash(), LeanString::from("kez").fast_hash());
+    /// ```
+    pub fn fast_hash(&self) -> u64 {
+        let bytes = self.as_bytes();
+        let mut hash = (bytes.len() as u64).wrapping_mul(K);
+
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            let mut word = [0; 8];
+            word.copy_from_slice(chunk);
+            hash = mix_word(hash, u64::from_le_bytes(word));
+        }
+        let remainder = chunks.remainder();
+        if !remainder.is_empty() {
+            hash = mix_word(hash, read_tail(remainder));
+        }
+
+        finalize(hash)
+    }
+}
@@ -0,0 +1,26 @@
+use memchr::{memchr, memmem};
+
+/// Returns an iterator over the start indices of non-overlapping matches of `needle`, using
+/// [`memmem`], if it is worth it.
+///
+/// `memmem` is much faster than `str::match_indices` for large haystacks, but setting up its
+/// searcher is not free. So this is used only for multi-byte needles on heap-sized haystacks, and
+/// returns `None` otherwise.
+#[inline]
+pub(crate) fn find_iter<'a>(
+    haystack: &'a str,
+    needle: &'a str,
+) -> Option<impl Iterator<Item = usize> + 'a> {
+    if needle.len() > 1 && haystack.len() > size_of::<crate::LeanString>() {
+        // The `Finder` for `needle` is built once here and reused for all matches.
+        Some(memmem::find_iter(haystack.as_bytes(), needle.as_bytes()))
+    } else {
+        None
+    }
+}
+
+/// Returns `true` if `haystack` contains `byte`, using [`memchr()`].
+#[inline]
+pub(crate) fn contains_byte(haystack: &[u8], byte: u8) -> bool {
+    memchr(byte, haystack).is_some()
+}
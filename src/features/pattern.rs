@@ -0,0 +1,58 @@
+use crate::LeanString;
+use core::str::pattern::{Pattern, ReverseSearcher, Utf8Pattern};
+
+/// A convenience impl that delegates to the impl for `&str`.
+///
+/// # Examples
+///
+/// ```
+/// # use lean_string::LeanString;
+/// let sep = LeanString::from(", ");
+/// let parts: Vec<&str> = "a, b, c".split(&sep).collect();
+/// assert_eq!(parts, ["a", "b", "c"]);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "nightly")))]
+impl<'b> Pattern for &'b LeanString {
+    type Searcher<'a> = <&'b str as Pattern>::Searcher<'a>;
+
+    #[inline]
+    fn into_searcher(self, haystack: &str) -> Self::Searcher<'_> {
+        self.as_str().into_searcher(haystack)
+    }
+
+    #[inline]
+    fn is_contained_in(self, haystack: &str) -> bool {
+        self.as_str().is_contained_in(haystack)
+    }
+
+    #[inline]
+    fn is_prefix_of(self, haystack: &str) -> bool {
+        self.as_str().is_prefix_of(haystack)
+    }
+
+    #[inline]
+    fn strip_prefix_of(self, haystack: &str) -> Option<&str> {
+        self.as_str().strip_prefix_of(haystack)
+    }
+
+    #[inline]
+    fn is_suffix_of<'a>(self, haystack: &'a str) -> bool
+    where
+        Self::Searcher<'a>: ReverseSearcher<'a>,
+    {
+        self.as_str().is_suffix_of(haystack)
+    }
+
+    #[inline]
+    fn strip_suffix_of<'a>(self, haystack: &'a str) -> Option<&'a str>
+    where
+        Self::Searcher<'a>: ReverseSearcher<'a>,
+    {
+        self.as_str().strip_suffix_of(haystack)
+    }
+
+    #[inline]
+    fn as_utf8_pattern(&self) -> Option<Utf8Pattern<'_>> {
+        Some(Utf8Pattern::StringPattern(self.as_str()))
+    }
+}
@@ -1,4 +1,4 @@
-use crate::LeanString;
+use crate::{repr::Repr, LeanString};
 use core::{fmt, str};
 use serde::de::{Deserializer, Error, Unexpected, Visitor};
 
@@ -47,3 +47,64 @@ impl<'de> serde::Deserialize<'de> for LeanString {
         deserializer.deserialize_string(LeanStringVisitor)
     }
 }
+
+impl LeanString {
+    /// Deserializes a [`LeanString`] that borrows `'static` data without copying it.
+    ///
+    /// serde can't tell whether the borrowed data lives for `'static`, so the
+    /// [`Deserialize`](serde::Deserialize) implementation always copies long strings. This
+    /// function requires a `Deserializer<'static>` (e.g. one reading from a `&'static str` or a
+    /// leaked buffer) instead, and builds a static buffer from the borrowed string, like
+    /// [`LeanString::from_static_str()`].
+    ///
+    /// Strings which the deserializer can't borrow (e.g. JSON strings containing escape
+    /// sequences) are copied as usual.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let json: &'static str = r#""This is a long string living in a static buffer""#;
+    ///
+    /// let mut deserializer = serde_json::Deserializer::from_str(json);
+    /// let s = LeanString::deserialize_static(&mut deserializer).unwrap();
+    /// assert_eq!(s, "This is a long string living in a static buffer");
+    /// assert!(!s.is_heap_allocated());
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+    pub fn deserialize_static<D: Deserializer<'static>>(deserializer: D) -> Result<Self, D::Error> {
+        struct StaticVisitor;
+
+        impl Visitor<'static> for StaticVisitor {
+            type Value = LeanString;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a string")
+            }
+
+            fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(LeanString::from(v))
+            }
+
+            fn visit_borrowed_str<E: Error>(self, v: &'static str) -> Result<Self::Value, E> {
+                Repr::from_static_str(v).map(LeanString).map_err(Error::custom)
+            }
+
+            fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                match str::from_utf8(v) {
+                    Ok(s) => Ok(LeanString::from(s)),
+                    Err(_) => Err(Error::invalid_value(Unexpected::Bytes(v), &self)),
+                }
+            }
+
+            fn visit_borrowed_bytes<E: Error>(self, v: &'static [u8]) -> Result<Self::Value, E> {
+                match str::from_utf8(v) {
+                    Ok(s) => self.visit_borrowed_str(s),
+                    Err(_) => Err(Error::invalid_value(Unexpected::Bytes(v), &self)),
+                }
+            }
+        }
+
+        deserializer.deserialize_str(StaticVisitor)
+    }
+}
@@ -0,0 +1,52 @@
+//! UTF-8 validation with a word-at-a-time (SWAR) fast path for ASCII.
+//!
+//! Most of the large text data is (mostly) ASCII, and checking that is just checking the highest
+//! bit of each byte. This fast path checks several words at a time, which compilers can vectorize,
+//! and hands the rest over to `core::str::from_utf8` once a non-ASCII byte is found.
+
+use core::str::{self, Utf8Error};
+
+const USIZE_SIZE: usize = size_of::<usize>();
+
+/// The number of bytes checked in a single iteration.
+const CHUNK_SIZE: usize = 4 * USIZE_SIZE;
+
+/// Inputs shorter than this are validated by `core::str::from_utf8` directly.
+const THRESHOLD: usize = 4 * CHUNK_SIZE;
+
+const HIGH_BITS: usize = usize::from_ne_bytes([0x80; USIZE_SIZE]);
+
+pub(crate) fn from_utf8(buf: &[u8]) -> Result<&str, Utf8Error> {
+    if buf.len() < THRESHOLD {
+        return str::from_utf8(buf);
+    }
+
+    let ascii_len = ascii_prefix_len(buf);
+
+    // `buf[..ascii_len]` is ASCII, so `ascii_len` is a char boundary.
+    match str::from_utf8(&buf[ascii_len..]) {
+        // SAFETY: `buf[..ascii_len]` is ASCII and `buf[ascii_len..]` is valid UTF-8.
+        Ok(_) => Ok(unsafe { str::from_utf8_unchecked(buf) }),
+        // Validate again to get the error relative to the whole `buf`.
+        Err(_) => str::from_utf8(buf),
+    }
+}
+
+/// Returns the length of the prefix of `buf` that is ASCII, rounded down to a multiple of
+/// [`CHUNK_SIZE`].
+#[inline]
+fn ascii_prefix_len(buf: &[u8]) -> usize {
+    let mut len = 0;
+    for chunk in buf.chunks_exact(CHUNK_SIZE) {
+        let mut acc = 0;
+        for word in chunk.chunks_exact(USIZE_SIZE) {
+            // SAFETY: `word` is exactly `USIZE_SIZE` bytes.
+            acc |= usize::from_ne_bytes(unsafe { word.try_into().unwrap_unchecked() });
+        }
+        if acc & HIGH_BITS != 0 {
+            break;
+        }
+        len += CHUNK_SIZE;
+    }
+    len
+}
@@ -0,0 +1,39 @@
+use crate::{LeanString, ReserveError, UnwrapWithMsg};
+use unicode_segmentation::UnicodeSegmentation;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "unicode-segmentation")))]
+impl LeanString {
+    /// Shortens the [`LeanString`] to at most `max_graphemes` extended grapheme clusters.
+    ///
+    /// Unlike truncating at a byte or [`char`] position, this never splits a user-perceived
+    /// character, such as an emoji with modifiers or a ZWJ sequence.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory when cloning the [`LeanString`]. If you want to
+    /// handle such a problem manually, use [`LeanString::try_truncate_graphemes()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// // "e" + U+0301 (combining acute accent) is 1 grapheme cluster
+    /// let mut s = LeanString::from("cafe\u{301}s");
+    /// s.truncate_graphemes(4);
+    /// assert_eq!(s, "cafe\u{301}");
+    /// ```
+    #[inline]
+    pub fn truncate_graphemes(&mut self, max_graphemes: usize) {
+        self.try_truncate_graphemes(max_graphemes).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::truncate_graphemes()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, but return an [`ReserveError`].
+    pub fn try_truncate_graphemes(&mut self, max_graphemes: usize) -> Result<(), ReserveError> {
+        match self.as_str().grapheme_indices(true).nth(max_graphemes) {
+            Some((new_len, _)) => self.0.truncate(new_len),
+            None => Ok(()),
+        }
+    }
+}
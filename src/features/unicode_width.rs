@@ -0,0 +1,26 @@
+use crate::LeanString;
+use unicode_width::UnicodeWidthStr;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "unicode-width")))]
+impl LeanString {
+    /// Returns the display width of the [`LeanString`] in columns, as in a terminal.
+    ///
+    /// East Asian wide and fullwidth characters (e.g. CJK ideographs) are 2 columns, and
+    /// zero-width characters such as combining marks are 0 columns, as computed by
+    /// [`UnicodeWidthStr::width()`]. This is useful for aligning the columns of a table in a
+    /// CLI, where [`LeanString::len()`] or the number of [`char`]s would be wrong.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// assert_eq!(LeanString::from("abc").display_width(), 3);
+    /// assert_eq!(LeanString::from("日本語").display_width(), 6);
+    /// // "e" + U+0301 (combining acute accent)
+    /// assert_eq!(LeanString::from("cafe\u{301}").display_width(), 4);
+    /// ```
+    #[inline]
+    pub fn display_width(&self) -> usize {
+        self.as_str().width()
+    }
+}
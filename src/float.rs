@@ -0,0 +1,236 @@
+/// The number of 32-bit limbs of [`Big`]. The largest number is `m * 10^p` where `m < 2^53`
+/// and `p <= 1074`, which is less than `2^3621` because `10^1074 < 2^3568`.
+const LIMBS: usize = 114;
+
+/// The number of decimal digits of a [`Big`] (at most 1091), rounded up to 9-digit chunks.
+const MAX_DIGITS: usize = 122 * 9;
+
+const ZEROS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// A finite [`f64`] rounded to a fixed number of fractional digits.
+///
+/// This is the same as `format!("{value:.precision$}")`: the exact binary value is rounded half
+/// to even, and the sign is kept for a negative zero. The digits are computed with an exact
+/// big integer on the stack, so nothing is allocated.
+pub(crate) struct FixedDecimal {
+    negative: bool,
+    digits: [u8; MAX_DIGITS],
+    /// `digits[start..]` are the digits of `|value| * 10^fraction`, without leading zeros (but at
+    /// least one digit).
+    start: usize,
+    /// The number of fractional digits in `digits[start..]`.
+    fraction: usize,
+    /// The number of zeros after them, so `fraction + zeros == precision`.
+    zeros: usize,
+}
+
+impl FixedDecimal {
+    pub(crate) fn new(value: f64, precision: usize) -> Self {
+        debug_assert!(value.is_finite());
+
+        let bits = value.to_bits();
+        let biased_exp = ((bits >> 52) & 0x7ff) as i32;
+        let mantissa = bits & ((1 << 52) - 1);
+        let (mut m, mut e) = match biased_exp {
+            0 => (mantissa, -1074),
+            _ => (mantissa | (1 << 52), biased_exp - 1075),
+        };
+        if m == 0 {
+            e = 0;
+        }
+        while e < 0 && m & 1 == 0 {
+            m >>= 1;
+            e += 1;
+        }
+
+        // value = m * 2^e, so it has exactly `-e` fractional digits if `e < 0`.
+        let mut n = Big::from_u64(m);
+        let fraction = if e >= 0 {
+            n.shl(e as usize);
+            0
+        } else {
+            let k = e.unsigned_abs() as usize;
+            let fraction = precision.min(k);
+            n.mul_pow10(fraction);
+            // n / 2^k, rounded half to even
+            let round_up = n.bit(k - 1) && (n.any_bit_below(k - 1) || n.bit(k));
+            n.shr(k);
+            if round_up {
+                n.add_one();
+            }
+            fraction
+        };
+
+        let mut digits = [b'0'; MAX_DIGITS];
+        let mut end = MAX_DIGITS;
+        loop {
+            let mut chunk = n.div_rem_small(1_000_000_000);
+            for digit in digits[end - 9..end].iter_mut().rev() {
+                *digit = b'0' + (chunk % 10) as u8;
+                chunk /= 10;
+            }
+            end -= 9;
+            if n.is_zero() {
+                break;
+            }
+        }
+        let start =
+            digits[..MAX_DIGITS - 1].iter().position(|&d| d != b'0').unwrap_or(MAX_DIGITS - 1);
+
+        FixedDecimal {
+            negative: value.is_sign_negative(),
+            digits,
+            start,
+            fraction,
+            zeros: precision - fraction,
+        }
+    }
+
+    /// Returns the length of the output, or `None` if it overflows `usize`.
+    pub(crate) fn len(&self) -> Option<usize> {
+        let digits = MAX_DIGITS - self.start;
+        let integer = digits.saturating_sub(self.fraction).max(1);
+        let precision = self.fraction + self.zeros;
+        let point = if precision > 0 { 1 } else { 0 };
+        (self.negative as usize + integer + point).checked_add(precision)
+    }
+
+    /// Writes the output in several parts by `push`.
+    pub(crate) fn write<E>(&self, mut push: impl FnMut(&str) -> Result<(), E>) -> Result<(), E> {
+        // SAFETY: `digits` contains only ASCII digits.
+        let digits = unsafe { core::str::from_utf8_unchecked(&self.digits[self.start..]) };
+
+        if self.negative {
+            push("-")?;
+        }
+        let leading_zeros = match digits.len().checked_sub(self.fraction) {
+            Some(integer) if integer > 0 => {
+                push(&digits[..integer])?;
+                0
+            }
+            _ => {
+                push("0")?;
+                self.fraction - digits.len()
+            }
+        };
+        if self.fraction + self.zeros == 0 {
+            return Ok(());
+        }
+        push(".")?;
+        if self.fraction > 0 {
+            push_zeros(leading_zeros, &mut push)?;
+            push(&digits[digits.len() - (self.fraction - leading_zeros)..])?;
+        }
+        push_zeros(self.zeros, &mut push)
+    }
+}
+
+fn push_zeros<E>(mut count: usize, push: &mut impl FnMut(&str) -> Result<(), E>) -> Result<(), E> {
+    while count > 0 {
+        let n = count.min(ZEROS.len());
+        push(&ZEROS[..n])?;
+        count -= n;
+    }
+    Ok(())
+}
+
+/// A fixed-size unsigned big integer, with little-endian limbs.
+struct Big {
+    limbs: [u32; LIMBS],
+    /// The number of used limbs. The limbs above it are zero.
+    len: usize,
+}
+
+impl Big {
+    fn from_u64(value: u64) -> Self {
+        let mut limbs = [0; LIMBS];
+        limbs[0] = value as u32;
+        limbs[1] = (value >> 32) as u32;
+        Big { limbs, len: 2 }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.limbs[..self.len].iter().all(|&limb| limb == 0)
+    }
+
+    fn bit(&self, i: usize) -> bool {
+        (self.limbs[i / 32] >> (i % 32)) & 1 == 1
+    }
+
+    fn any_bit_below(&self, i: usize) -> bool {
+        let mask = (1u32 << (i % 32)) - 1;
+        self.limbs[..i / 32].iter().any(|&limb| limb != 0) || self.limbs[i / 32] & mask != 0
+    }
+
+    fn mul_small(&mut self, x: u32) {
+        let mut carry = 0;
+        for limb in &mut self.limbs[..self.len] {
+            let v = *limb as u64 * x as u64 + carry;
+            *limb = v as u32;
+            carry = v >> 32;
+        }
+        if carry > 0 {
+            self.limbs[self.len] = carry as u32;
+            self.len += 1;
+        }
+    }
+
+    fn mul_pow10(&mut self, mut exp: usize) {
+        while exp >= 9 {
+            self.mul_small(1_000_000_000);
+            exp -= 9;
+        }
+        self.mul_small(10u32.pow(exp as u32));
+    }
+
+    fn shl(&mut self, bits: usize) {
+        let (limbs, bits) = (bits / 32, bits % 32);
+        let new_len = self.len + limbs + 1;
+        for i in (0..new_len).rev() {
+            let hi = i.checked_sub(limbs).map_or(0, |j| self.limbs.get(j).copied().unwrap_or(0));
+            let lo = i.checked_sub(limbs + 1).map_or(0, |j| self.limbs[j]);
+            self.limbs[i] = match bits {
+                0 => hi,
+                _ => (hi << bits) | (lo >> (32 - bits)),
+            };
+        }
+        self.len = new_len;
+    }
+
+    fn shr(&mut self, bits: usize) {
+        let (limbs, bits) = (bits / 32, bits % 32);
+        for i in 0..self.len {
+            let lo = self.limbs.get(i + limbs).copied().unwrap_or(0);
+            let hi = self.limbs.get(i + limbs + 1).copied().unwrap_or(0);
+            self.limbs[i] = match bits {
+                0 => lo,
+                _ => (lo >> bits) | (hi << (32 - bits)),
+            };
+        }
+        self.len = self.len.saturating_sub(limbs).max(1);
+        self.limbs[self.len..].fill(0);
+    }
+
+    fn add_one(&mut self) {
+        for limb in &mut self.limbs[..self.len] {
+            let (v, overflow) = limb.overflowing_add(1);
+            *limb = v;
+            if !overflow {
+                return;
+            }
+        }
+        self.limbs[self.len] = 1;
+        self.len += 1;
+    }
+
+    /// Divides by `d` in-place and returns the remainder.
+    fn div_rem_small(&mut self, d: u32) -> u32 {
+        let mut rem = 0u64;
+        for limb in self.limbs[..self.len].iter_mut().rev() {
+            let v = (rem << 32) | *limb as u64;
+            *limb = (v / d as u64) as u32;
+            rem = v % d as u64;
+        }
+        rem as u32
+    }
+}
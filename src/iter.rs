@@ -0,0 +1,261 @@
+use crate::LeanString;
+use core::{fmt, iter::FusedIterator};
+
+/// An owning iterator over the [`char`]s of a [`LeanString`].
+///
+/// This struct is created by [`LeanString::into_chars()`]. See its documentation for more.
+#[derive(Clone)]
+pub struct IntoChars {
+    string: LeanString,
+    // INVARIANT: `front <= back <= string.len()`, and both lie on char boundaries.
+    front: usize,
+    back: usize,
+}
+
+impl IntoChars {
+    #[inline]
+    pub(crate) fn new(string: LeanString) -> Self {
+        let back = string.len();
+        IntoChars { string, front: 0, back }
+    }
+
+    /// Views the remaining characters as a string slice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut chars = LeanString::from("abc").into_chars();
+    /// assert_eq!(chars.as_str(), "abc");
+    ///
+    /// chars.next();
+    /// chars.next_back();
+    /// assert_eq!(chars.as_str(), "b");
+    /// ```
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        // SAFETY: By the invariant, `front..back` is in bounds and lies on char boundaries.
+        unsafe { self.string.as_str().get_unchecked(self.front..self.back) }
+    }
+}
+
+impl Iterator for IntoChars {
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<char> {
+        let ch = self.as_str().chars().next()?;
+        self.front += ch.len_utf8();
+        Some(ch)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.as_str().chars().size_hint()
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.as_str().chars().count()
+    }
+
+    #[inline]
+    fn last(mut self) -> Option<char> {
+        self.next_back()
+    }
+}
+
+impl DoubleEndedIterator for IntoChars {
+    #[inline]
+    fn next_back(&mut self) -> Option<char> {
+        let ch = self.as_str().chars().next_back()?;
+        self.back -= ch.len_utf8();
+        Some(ch)
+    }
+}
+
+impl FusedIterator for IntoChars {}
+
+impl fmt::Debug for IntoChars {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoChars").field(&self.as_str()).finish()
+    }
+}
+
+/// An owning iterator over the [`char`]s of a [`LeanString`], and their byte positions.
+///
+/// This struct is created by [`LeanString::into_char_indices()`]. See its documentation for
+/// more.
+#[derive(Clone)]
+pub struct IntoCharIndices {
+    string: LeanString,
+    // INVARIANT: `front <= back <= string.len()`, and both lie on char boundaries.
+    front: usize,
+    back: usize,
+}
+
+impl IntoCharIndices {
+    #[inline]
+    pub(crate) fn new(string: LeanString) -> Self {
+        let back = string.len();
+        IntoCharIndices { string, front: 0, back }
+    }
+
+    /// Views the remaining characters as a string slice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut indices = LeanString::from("abc").into_char_indices();
+    /// assert_eq!(indices.as_str(), "abc");
+    ///
+    /// indices.next();
+    /// indices.next_back();
+    /// assert_eq!(indices.as_str(), "b");
+    /// ```
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        // SAFETY: By the invariant, `front..back` is in bounds and lies on char boundaries.
+        unsafe { self.string.as_str().get_unchecked(self.front..self.back) }
+    }
+}
+
+impl Iterator for IntoCharIndices {
+    type Item = (usize, char);
+
+    #[inline]
+    fn next(&mut self) -> Option<(usize, char)> {
+        let ch = self.as_str().chars().next()?;
+        let idx = self.front;
+        self.front += ch.len_utf8();
+        Some((idx, ch))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.as_str().chars().size_hint()
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.as_str().chars().count()
+    }
+
+    #[inline]
+    fn last(mut self) -> Option<(usize, char)> {
+        self.next_back()
+    }
+}
+
+impl DoubleEndedIterator for IntoCharIndices {
+    #[inline]
+    fn next_back(&mut self) -> Option<(usize, char)> {
+        let ch = self.as_str().chars().next_back()?;
+        self.back -= ch.len_utf8();
+        Some((self.back, ch))
+    }
+}
+
+impl FusedIterator for IntoCharIndices {}
+
+impl fmt::Debug for IntoCharIndices {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoCharIndices").field(&self.as_str()).finish()
+    }
+}
+
+/// An owning iterator over the bytes of a [`LeanString`].
+///
+/// This struct is created by [`LeanString::into_bytes_iter()`]. See its documentation for more.
+#[derive(Clone)]
+pub struct IntoBytes {
+    string: LeanString,
+    // INVARIANT: `front <= back <= string.len()`.
+    front: usize,
+    back: usize,
+}
+
+impl IntoBytes {
+    #[inline]
+    pub(crate) fn new(string: LeanString) -> Self {
+        let back = string.len();
+        IntoBytes { string, front: 0, back }
+    }
+
+    /// Views the remaining bytes as a slice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut bytes = LeanString::from("abc").into_bytes_iter();
+    /// assert_eq!(bytes.as_slice(), b"abc");
+    ///
+    /// bytes.next();
+    /// assert_eq!(bytes.as_slice(), b"bc");
+    /// ```
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        // SAFETY: By the invariant, `front..back` is in bounds.
+        unsafe { self.string.as_bytes().get_unchecked(self.front..self.back) }
+    }
+
+    /// Releases the buffer as soon as all bytes are consumed, instead of waiting for the drop.
+    #[inline]
+    fn release_if_exhausted(&mut self) {
+        if self.front == self.back {
+            self.string = LeanString::new();
+            self.front = 0;
+            self.back = 0;
+        }
+    }
+}
+
+impl Iterator for IntoBytes {
+    type Item = u8;
+
+    #[inline]
+    fn next(&mut self) -> Option<u8> {
+        let byte = *self.as_slice().first()?;
+        self.front += 1;
+        self.release_if_exhausted();
+        Some(byte)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn last(mut self) -> Option<u8> {
+        self.next_back()
+    }
+}
+
+impl DoubleEndedIterator for IntoBytes {
+    #[inline]
+    fn next_back(&mut self) -> Option<u8> {
+        let byte = *self.as_slice().last()?;
+        self.back -= 1;
+        self.release_if_exhausted();
+        Some(byte)
+    }
+}
+
+impl ExactSizeIterator for IntoBytes {}
+
+impl FusedIterator for IntoBytes {}
+
+impl fmt::Debug for IntoBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoBytes").field(&self.as_slice()).finish()
+    }
+}
@@ -1,5 +1,6 @@
 #![doc = include_str!("../README.md")]
 #![cfg_attr(docsrs, feature(doc_cfg))]
+#![cfg_attr(feature = "nightly", feature(extend_one, pattern))]
 #![no_std]
 
 extern crate alloc;
@@ -11,18 +12,19 @@ use core::{
     borrow::Borrow,
     cmp, fmt,
     hash::{Hash, Hasher},
-    ops::{Add, AddAssign, Deref},
+    mem::MaybeUninit,
+    ops::{Add, AddAssign, Bound, Deref, Range, RangeBounds},
     str,
     str::FromStr,
 };
 
-use alloc::{borrow::Cow, boxed::Box, string::String};
+use alloc::{borrow::Cow, boxed::Box, rc::Rc, string::String, sync::Arc};
 
 #[cfg(feature = "std")]
-use std::ffi::OsStr;
+use std::{ffi::OsStr, path::Path};
 
 mod repr;
-use repr::Repr;
+use repr::{Repr, MAX_HEAP_CAPACITY, MAX_INLINE_SIZE};
 
 #[cfg(feature = "last_byte")]
 pub use repr::LastByte;
@@ -33,21 +35,102 @@ pub use errors::*;
 mod traits;
 pub use traits::ToLeanString;
 
+mod builder;
+pub use builder::LeanStringBuilder;
+
+mod iter;
+pub use iter::{IntoBytes, IntoCharIndices, IntoChars};
+
+mod writer;
+pub use writer::FallibleWriter;
+
+mod scoped;
+pub use scoped::ScopedLen;
+
+mod byte_key;
+pub use byte_key::ByteKey;
+
+mod case_insensitive;
+pub use case_insensitive::AsciiCaseInsensitive;
+
+mod raw;
+pub use raw::RawParts;
+
+mod float;
+use float::FixedDecimal;
+
 mod features;
+#[cfg(feature = "alloc-hooks")]
+pub use features::alloc_hooks::{clear_alloc_observer, set_alloc_observer, AllocEvent};
 
 #[repr(transparent)]
 pub struct LeanString(Repr);
 
+/// An alias of [`LeanString`].
+///
+/// This crate was originally developed under the name `small_string`, and this alias is kept for
+/// users who expect that name.
+pub type SmallString = LeanString;
+
 fn _static_assert() {
     const {
-        assert!(size_of::<LeanString>() == 2 * size_of::<usize>());
-        assert!(size_of::<Option<LeanString>>() == 2 * size_of::<usize>());
+        let words = if cfg!(feature = "wide_inline") { 3 } else { 2 };
+        assert!(size_of::<LeanString>() == words * size_of::<usize>());
+        assert!(size_of::<Option<LeanString>>() == words * size_of::<usize>());
         assert!(align_of::<LeanString>() == align_of::<usize>());
         assert!(align_of::<Option<LeanString>>() == align_of::<usize>());
     }
 }
 
 impl LeanString {
+    /// The number of bytes a [`LeanString`] can hold without heap allocation.
+    ///
+    /// This is the same as `size_of::<LeanString>()`: 16 bytes on 64-bit architecture and 8 bytes
+    /// on 32-bit architecture (24 and 12 bytes with the `wide_inline` feature).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// const CAP: usize = LeanString::INLINE_CAPACITY;
+    /// assert_eq!(CAP, size_of::<LeanString>());
+    ///
+    /// let s = LeanString::from("a".repeat(CAP));
+    /// assert!(!s.is_heap_allocated());
+    /// ```
+    pub const INLINE_CAPACITY: usize = MAX_INLINE_SIZE;
+
+    /// The maximum capacity of a heap allocated [`LeanString`].
+    ///
+    /// - On 64-bit architecture, this is `2^56 - 1`.
+    /// - On 32-bit architecture, this is `2^24 - 2`.
+    ///
+    /// A larger heap buffer is never allocated: the `try_` methods such as
+    /// [`LeanString::try_with_capacity()`] and [`LeanString::try_reserve()`] return a
+    /// [`ReserveError`] for it, and the other methods panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// assert!(LeanString::try_with_capacity(LeanString::MAX_HEAP_CAPACITY + 1).is_err());
+    /// ```
+    pub const MAX_HEAP_CAPACITY: usize = MAX_HEAP_CAPACITY;
+
+    /// An empty [`LeanString`], the same as [`LeanString::new()`].
+    ///
+    /// This is useful as a default value of a field or in `const` contexts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// const NAME: LeanString = LeanString::EMPTY;
+    /// assert!(NAME.is_empty());
+    /// assert_eq!(NAME, LeanString::new());
+    /// ```
+    pub const EMPTY: LeanString = LeanString::new();
+
     /// Creates a new empty [`LeanString`].
     ///
     /// Same as [`String::new()`], this will not allocate on the heap.
@@ -87,8 +170,9 @@ impl LeanString {
     /// Creates a new empty [`LeanString`] with at least capacity bytes.
     ///
     /// A [`LeanString`] will inline strings if the length is less than or equal to
-    /// `2 * size_of::<usize>()` bytes. This means that the minimum capacity of a [`LeanString`]
-    /// is `2 * size_of::<usize>()` bytes.
+    /// `size_of::<LeanString>()` bytes (`2 * size_of::<usize>()`, or `3 * size_of::<usize>()` with
+    /// the `wide_inline` feature). This means that the minimum capacity of a [`LeanString`] is
+    /// `size_of::<LeanString>()` bytes.
     ///
     /// # Panics
     ///
@@ -96,7 +180,7 @@ impl LeanString {
     ///
     /// - The system is out-of-memory.
     /// - On 64-bit architecture, the `capacity` is greater than `2^56 - 1`.
-    /// - On 32-bit architecture, the `capacity` is greater than `2^32 - 1`.
+    /// - On 32-bit architecture, the `capacity` is greater than `2^24 - 2`.
     ///
     /// If you want to handle such a problem manually, use [`LeanString::try_with_capacity()`].
     ///
@@ -107,7 +191,7 @@ impl LeanString {
     /// ```
     /// # use lean_string::LeanString;
     /// let s = LeanString::with_capacity(4);
-    /// assert_eq!(s.capacity(), 2 * size_of::<usize>());
+    /// assert_eq!(s.capacity(), size_of::<LeanString>());
     /// assert!(!s.is_heap_allocated());
     /// ```
     ///
@@ -126,17 +210,281 @@ impl LeanString {
 
     /// Fallible version of [`LeanString::with_capacity()`].
     ///
-    /// This method won't panic if the system is out-of-memory, or the `capacity` is too large, but
-    /// return an [`ReserveError`]. Otherwise it behaves the same as [`LeanString::with_capacity()`].
+    /// This method won't panic if the system is out-of-memory, or the `capacity` is greater than
+    /// [`LeanString::MAX_HEAP_CAPACITY`] (`2^24 - 2` on 32-bit architecture), but return an
+    /// [`ReserveError`]. Otherwise it behaves the same as [`LeanString::with_capacity()`].
     #[inline]
     pub fn try_with_capacity(capacity: usize) -> Result<Self, ReserveError> {
         Repr::with_capacity(capacity).map(LeanString)
     }
 
+    /// Creates a new empty [`LeanString`] with the same capacity as `other`.
+    ///
+    /// This is useful for building a sibling buffer, e.g. in double-buffering code.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::with_capacity(100);
+    /// let sibling = LeanString::with_capacity_like(&s);
+    /// assert!(sibling.is_empty());
+    /// assert_eq!(sibling.capacity(), 100);
+    /// ```
+    #[inline]
+    pub fn with_capacity_like(other: &LeanString) -> Self {
+        LeanString::with_capacity(other.capacity())
+    }
+
+    /// Creates a new [`LeanString`] from `s`, with capacity for at least `extra` more bytes.
+    ///
+    /// This is useful when you know you'll append more, so the following pushes don't
+    /// immediately reallocate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory, or the capacity is too large. If you want to handle
+    /// such a problem manually, use [`LeanString::try_from_str_with_headroom()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::from_str_with_headroom("Hello", 100);
+    /// assert_eq!(s, "Hello");
+    /// assert_eq!(s.capacity(), 105);
+    ///
+    /// s.push_str(", world!");
+    /// assert_eq!(s.capacity(), 105);
+    /// ```
+    #[inline]
+    pub fn from_str_with_headroom(s: &str, extra: usize) -> Self {
+        LeanString::try_from_str_with_headroom(s, extra).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::from_str_with_headroom()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, or the capacity is too large, but
+    /// return an [`ReserveError`]. Otherwise it behaves the same as
+    /// [`LeanString::from_str_with_headroom()`].
+    #[inline]
+    pub fn try_from_str_with_headroom(s: &str, extra: usize) -> Result<Self, ReserveError> {
+        let capacity = s.len().checked_add(extra).ok_or(ReserveError)?;
+        let mut result = LeanString::try_with_capacity(capacity)?;
+        result.try_push_str(s)?;
+        Ok(result)
+    }
+
+    /// Creates a new empty [`LeanString`] with enough capacity to hold the concatenation of
+    /// `strs`.
+    ///
+    /// The capacity is the exact sum of the lengths of `strs` (or the inline capacity if the sum
+    /// fits inline), so pushing all of `strs` afterwards does not reallocate.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ReserveError`] if the system is out-of-memory, or the total length is too
+    /// large.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let pieces = ["Hello", ", ", "world", "! ", "How are you?"];
+    /// let mut s = LeanString::with_capacity_for(&pieces).unwrap();
+    /// assert_eq!(s.capacity(), 26);
+    ///
+    /// for piece in pieces {
+    ///     s.push_str(piece);
+    /// }
+    /// assert_eq!(s, "Hello, world! How are you?");
+    /// assert_eq!(s.capacity(), 26);
+    /// ```
+    #[inline]
+    pub fn with_capacity_for(strs: &[&str]) -> Result<Self, ReserveError> {
+        let total = strs.iter().try_fold(0usize, |acc, s| acc.checked_add(s.len()));
+        LeanString::try_with_capacity(total.ok_or(ReserveError)?)
+    }
+
+    /// Creates a new [`LeanString`] by joining `pieces` with the [`char`] separator `sep`.
+    ///
+    /// The total length, including the separators, is computed up front, so the result is
+    /// allocated at most once. No separator is emitted for an empty or single-element slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory, or the total length is too large. If you want to
+    /// handle such a problem manually, use [`LeanString::try_join_chars()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let pieces = [LeanString::from("a"), LeanString::from("b"), LeanString::from("c")];
+    /// assert_eq!(LeanString::join_chars(&pieces, ','), "a,b,c");
+    ///
+    /// assert_eq!(LeanString::join_chars(&pieces[..1], ','), "a");
+    /// assert_eq!(LeanString::join_chars(&[], ','), "");
+    /// ```
+    #[inline]
+    pub fn join_chars(pieces: &[LeanString], sep: char) -> Self {
+        LeanString::try_join_chars(pieces, sep).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::join_chars()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, or the total length is too large,
+    /// but return an [`ReserveError`]. Otherwise it behaves the same as
+    /// [`LeanString::join_chars()`].
+    pub fn try_join_chars(pieces: &[LeanString], sep: char) -> Result<Self, ReserveError> {
+        let Some((first, rest)) = pieces.split_first() else {
+            return Ok(LeanString::new());
+        };
+        let mut buf = [0; 4];
+        let sep = sep.encode_utf8(&mut buf);
+
+        let total = rest
+            .len()
+            .checked_mul(sep.len())
+            .and_then(|seps| pieces.iter().try_fold(seps, |acc, s| acc.checked_add(s.len())));
+        let mut result = LeanString::try_with_capacity(total.ok_or(ReserveError)?)?;
+
+        result.try_push_str(first)?;
+        for piece in rest {
+            result.try_push_str(sep)?;
+            result.try_push_str(piece)?;
+        }
+        Ok(result)
+    }
+
+    /// Creates a new [`LeanString`] by concatenating `pieces`, skipping a piece which is identical
+    /// to the immediately preceding one.
+    ///
+    /// Only consecutive duplicates are collapsed, non-adjacent duplicates are kept. This is useful
+    /// for building normalized path-like strings.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory, or the total length is too large. If you want to
+    /// handle such a problem manually, use [`LeanString::try_from_iter_dedup()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from_iter_dedup(["a", "/", "/", "b", "/", "a", "a"]);
+    /// assert_eq!(s, "a/b/a");
+    /// ```
+    #[inline]
+    pub fn from_iter_dedup<'a>(pieces: impl IntoIterator<Item = &'a str>) -> Self {
+        LeanString::try_from_iter_dedup(pieces).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::from_iter_dedup()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, or the total length is too large,
+    /// but return an [`ReserveError`]. Otherwise it behaves the same as
+    /// [`LeanString::from_iter_dedup()`].
+    pub fn try_from_iter_dedup<'a>(
+        pieces: impl IntoIterator<Item = &'a str>,
+    ) -> Result<Self, ReserveError> {
+        let mut result = LeanString::new();
+        let mut last = None;
+        for piece in pieces {
+            if last != Some(piece) {
+                result.try_push_str(piece)?;
+                last = Some(piece);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Creates a new [`LeanString`] from [`fmt::Arguments`].
+    ///
+    /// The output is written directly into the [`LeanString`] without an intermediate
+    /// [`String`], so this is usually more efficient than `format!(...).into()`. If the arguments
+    /// are a plain string literal, a static buffer is used and nothing is copied.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory, or a formatting trait implementation returns an
+    /// error. If you want to handle such a problem manually, use [`LeanString::try_from_fmt()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let (x, y) = (1, 2);
+    /// let s = LeanString::from_fmt(format_args!("{x}-{y}"));
+    /// assert_eq!(s, "1-2");
+    /// assert!(!s.is_heap_allocated());
+    /// ```
+    #[inline]
+    pub fn from_fmt(args: fmt::Arguments<'_>) -> Self {
+        LeanString::try_from_fmt(args).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::from_fmt()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, or a formatting trait
+    /// implementation returns an error, but return an [`ToLeanStringError`]. Otherwise it behaves
+    /// the same as [`LeanString::from_fmt()`].
+    pub fn try_from_fmt(args: fmt::Arguments<'_>) -> Result<Self, ToLeanStringError> {
+        if let Some(text) = args.as_str() {
+            return Ok(LeanString(Repr::from_static_str(text)?));
+        }
+        let mut result = LeanString::new();
+        let mut writer = FallibleWriter::new(&mut result);
+        let written = fmt::Write::write_fmt(&mut writer, args);
+        writer.into_result()?;
+        written?;
+        Ok(result)
+    }
+
+    /// Creates a new [`LeanString`] from at most `max_bytes` bytes of `text`.
+    ///
+    /// If `max_bytes` does not lie on a [`char`] boundary, the result is truncated to the nearest
+    /// boundary below it, so a character is never split.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from_str_truncated("Hello, world!", 5);
+    /// assert_eq!(s, "Hello");
+    ///
+    /// // 'ö' is 2 bytes, and it is not split.
+    /// let s = LeanString::from_str_truncated("Löwe", 2);
+    /// assert_eq!(s, "L");
+    ///
+    /// let s = LeanString::from_str_truncated("short", 100);
+    /// assert_eq!(s, "short");
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn from_str_truncated(text: &str, max_bytes: usize) -> Self {
+        let mut end = max_bytes.min(text.len());
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        LeanString::from(&text[..end])
+    }
+
     /// Converts a slice of bytes to a [`LeanString`].
     ///
     /// If the slice is not valid UTF-8, an error is returned.
     ///
+    /// With the `simd` feature, long inputs are validated with a word-at-a-time fast path for
+    /// ASCII bytes.
+    ///
     /// # Examples
     ///
     /// ## valid UTF-8
@@ -160,10 +508,102 @@ impl LeanString {
     /// ```
     #[inline]
     pub fn from_utf8(buf: &[u8]) -> Result<Self, str::Utf8Error> {
+        #[cfg(feature = "simd")]
+        let str = features::simd::from_utf8(buf)?;
+        #[cfg(not(feature = "simd"))]
         let str = str::from_utf8(buf)?;
         Ok(LeanString::from(str))
     }
 
+    /// Copies a slice of bytes into a new [`LeanString`], returning an error if `bytes` is not
+    /// valid UTF-8.
+    ///
+    /// This is the same as [`LeanString::from_utf8()`], but the error doesn't carry any details
+    /// about the invalid sequence.
+    ///
+    /// # Note
+    ///
+    /// There is no counterpart of [`str::from_utf8_mut()`] which wraps a borrowed `&mut [u8]`,
+    /// because [`LeanString`] either owns its buffer or borrows a `&'static str`. The bytes are
+    /// always copied, into the inline buffer or a new heap buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::{LeanString, FromUtf8Error};
+    /// let mut buf = *b"hello";
+    /// let s = LeanString::from_utf8_slice_copy(&buf).unwrap();
+    ///
+    /// buf[0] = b'j';
+    /// assert_eq!(s, "hello");
+    ///
+    /// assert_eq!(LeanString::from_utf8_slice_copy(&[0xFF]), Err(FromUtf8Error));
+    /// ```
+    #[inline]
+    pub fn from_utf8_slice_copy(bytes: &[u8]) -> Result<Self, FromUtf8Error> {
+        LeanString::from_utf8(bytes).map_err(|_| FromUtf8Error)
+    }
+
+    /// Converts a `&'static [u8]` to a [`LeanString`] without copying, returning an error if
+    /// `bytes` is not valid UTF-8.
+    ///
+    /// This is the same as [`LeanString::from_static_str()`] after validating `bytes`, so a long
+    /// input borrows the static bytes and nothing is allocated. This is useful for embedded data,
+    /// such as `include_bytes!`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `bytes` is greater than [`LeanString::MAX_HEAP_CAPACITY`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::{LeanString, FromUtf8Error};
+    /// static TEXT: &[u8] = "a long text embedded in the binary 🦀".as_bytes();
+    ///
+    /// let s = LeanString::from_static_utf8(TEXT).unwrap();
+    /// assert_eq!(s, "a long text embedded in the binary 🦀");
+    /// assert_eq!(s.as_ptr(), TEXT.as_ptr());
+    /// assert!(!s.is_heap_allocated());
+    ///
+    /// assert_eq!(LeanString::from_static_utf8(b"\xFF"), Err(FromUtf8Error));
+    /// ```
+    #[inline]
+    pub fn from_static_utf8(bytes: &'static [u8]) -> Result<Self, FromUtf8Error> {
+        #[cfg(feature = "simd")]
+        let str = features::simd::from_utf8(bytes);
+        #[cfg(not(feature = "simd"))]
+        let str = str::from_utf8(bytes);
+        str.map(LeanString::from_static_str).map_err(|_| FromUtf8Error)
+    }
+
+    /// Converts a byte array to a [`LeanString`], returning an error if `bytes` is not valid
+    /// UTF-8.
+    ///
+    /// This is handy for fixed-size tokens, such as ASCII codes. If `N` is less than or equal to
+    /// `size_of::<LeanString>()`, the result is inline and nothing is allocated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory when `N` doesn't fit inline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::{LeanString, FromUtf8Error};
+    /// let s = LeanString::from_ascii_array(*b"USD");
+    /// assert_eq!(s, Ok(LeanString::from("USD")));
+    ///
+    /// assert_eq!(LeanString::from_ascii_array([b'a', 0xFF]), Err(FromUtf8Error));
+    /// ```
+    #[inline]
+    pub fn from_ascii_array<const N: usize>(bytes: [u8; N]) -> Result<Self, FromUtf8Error> {
+        match str::from_utf8(&bytes) {
+            Ok(str) => Ok(LeanString::from(str)),
+            Err(_) => Err(FromUtf8Error),
+        }
+    }
+
     /// Converts a slice of bytes to a [`LeanString`], including invalid characters.
     ///
     /// During this conversion, all invalid characters are replaced with the
@@ -197,8 +637,12 @@ impl LeanString {
     ///
     /// This function is unsafe because it does not check that the bytes passed to it are valid
     /// UTF-8. If this constraint is violated, it may cause memory unsafety issues.
+    ///
+    /// In debug builds, this panics if the bytes are not valid UTF-8, to catch misuse early.
     #[inline]
+    #[track_caller]
     pub unsafe fn from_utf8_unchecked(buf: &[u8]) -> Self {
+        debug_assert!(str::from_utf8(buf).is_ok(), "bytes are not valid UTF-8");
         let str = unsafe { str::from_utf8_unchecked(buf) };
         LeanString::from(str)
     }
@@ -223,8 +667,24 @@ impl LeanString {
     /// let v = &[0xD834, 0xDD1E, 0x006d, 0x0075, 0xD800, 0x0069, 0x0063];
     /// assert!(String::from_utf16(v).is_err());
     /// ```
-    #[inline]
     pub fn from_utf16(buf: &[u16]) -> Result<Self, FromUtf16Error> {
+        const STACK_UNITS: usize = MAX_INLINE_SIZE;
+
+        if buf.len() <= STACK_UNITS {
+            // Decode a short input on the stack first, so that the result is inline without
+            // allocation if it fits, or allocated once with the exact length otherwise.
+            // A code unit is decoded to at most 3 bytes (a surrogate pair of 2 units to 4 bytes).
+            let mut stack = [0; STACK_UNITS * 3];
+            let mut len = 0;
+            for c in char::decode_utf16(buf.iter().copied()) {
+                let c = c.map_err(|_| FromUtf16Error)?;
+                len += c.encode_utf8(&mut stack[len..]).len();
+            }
+            // SAFETY: `stack[..len]` consists of UTF-8 encoded chars.
+            let str = unsafe { str::from_utf8_unchecked(&stack[..len]) };
+            return Ok(LeanString::from(str));
+        }
+
         let mut ret = LeanString::with_capacity(buf.len());
         for c in char::decode_utf16(buf.iter().copied()) {
             match c {
@@ -267,7 +727,7 @@ impl LeanString {
     /// assert_eq!(fancy_f.chars().count(), 3);
     /// ```
     #[inline]
-    pub fn len(&self) -> usize {
+    pub const fn len(&self) -> usize {
         self.0.len()
     }
 
@@ -284,15 +744,16 @@ impl LeanString {
     /// assert!(!s.is_empty());
     /// ```
     #[inline]
-    pub fn is_empty(&self) -> bool {
+    pub const fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
 
     /// Returns the capacity of the [`LeanString`], in bytes.
     ///
     /// A [`LeanString`] will inline strings if the length is less than or equal to
-    /// `2 * size_of::<usize>()` bytes. This means that the minimum capacity of a [`LeanString`]
-    /// is `2 * size_of::<usize>()` bytes.
+    /// `size_of::<LeanString>()` bytes (`2 * size_of::<usize>()`, or `3 * size_of::<usize>()` with
+    /// the `wide_inline` feature). This means that the minimum capacity of a [`LeanString`] is
+    /// `size_of::<LeanString>()` bytes.
     ///
     /// # Examples
     ///
@@ -301,7 +762,7 @@ impl LeanString {
     /// ```
     /// # use lean_string::LeanString;
     /// let s = LeanString::new();
-    /// assert_eq!(s.capacity(), 2 * size_of::<usize>());
+    /// assert_eq!(s.capacity(), size_of::<LeanString>());
     /// ```
     ///
     /// ## heap capacity
@@ -316,6 +777,31 @@ impl LeanString {
         self.0.capacity()
     }
 
+    /// Returns the number of bytes that can be appended before the [`LeanString`] needs to grow.
+    ///
+    /// This is the same as `self.capacity() - self.len()`, which never underflows because the
+    /// length is always less than or equal to the capacity.
+    ///
+    /// # Note
+    ///
+    /// A [`LeanString`] created from a `&'static str` has no remaining capacity, and a shared
+    /// heap buffer is cloned when it is modified even if it has remaining capacity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from("abc");
+    /// assert_eq!(s.remaining_capacity(), size_of::<LeanString>() - 3);
+    ///
+    /// let s = LeanString::from_static_str("This is a static string, long enough");
+    /// assert_eq!(s.remaining_capacity(), 0);
+    /// ```
+    #[inline]
+    pub fn remaining_capacity(&self) -> usize {
+        self.capacity() - self.len()
+    }
+
     /// Returns a string slice containing the entire [`LeanString`].
     ///
     /// # Examples
@@ -344,333 +830,2237 @@ impl LeanString {
         self.0.as_bytes()
     }
 
-    /// Reserves capacity for at least `additional` bytes more than the current length.
+    /// Finds the closest `x` not exceeding `index` where [`str::is_char_boundary(x)`] is `true`.
     ///
-    /// # Note
+    /// If `index` is greater than the length of the string, this returns the length of the
+    /// string. This is useful to snap a byte index before slicing or truncating.
     ///
-    /// This method clones the [`LeanString`] if it is not unique.
+    /// [`str::is_char_boundary(x)`]: str::is_char_boundary
     ///
-    /// # Panics
+    /// # Examples
     ///
-    /// Panics if any of the following conditions are met:
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from("❤️🧡");
+    /// assert_eq!(s.len(), 10);
     ///
-    /// - The system is out-of-memory.
-    /// - On 64-bit architecture, the `capacity` is greater than `2^56 - 1`.
-    /// - On 32-bit architecture, the `capacity` is greater than `2^32 - 1`.
+    /// // `❤️` is 6 bytes (U+2764 and U+FE0F), `🧡` is 4 bytes.
+    /// assert_eq!(s.floor_char_boundary(5), 3);
+    /// assert_eq!(s.floor_char_boundary(8), 6);
+    /// assert_eq!(s.floor_char_boundary(20), 10);
+    /// ```
+    #[inline]
+    pub fn floor_char_boundary(&self, index: usize) -> usize {
+        let bytes = self.as_bytes();
+        if index >= bytes.len() {
+            return bytes.len();
+        }
+        // A char is at most 4 bytes, so the boundary is found within 3 steps.
+        let mut i = index;
+        while !is_utf8_char_boundary(bytes[i]) {
+            i -= 1;
+        }
+        i
+    }
+
+    /// Finds the closest `x` not below `index` where [`str::is_char_boundary(x)`] is `true`.
     ///
-    /// If you want to handle such a problem manually, use [`LeanString::try_reserve()`].
+    /// If `index` is greater than the length of the string, this returns the length of the
+    /// string.
+    ///
+    /// [`str::is_char_boundary(x)`]: str::is_char_boundary
     ///
     /// # Examples
     ///
     /// ```
     /// # use lean_string::LeanString;
-    /// let mut s = LeanString::new();
+    /// let s = LeanString::from("❤️🧡");
     ///
-    /// // We have a inline strage on the stack.
-    /// assert_eq!(s.capacity(), 2 * size_of::<usize>());
-    /// assert!(!s.is_heap_allocated());
+    /// assert_eq!(s.ceil_char_boundary(1), 3);
+    /// assert_eq!(s.ceil_char_boundary(7), 10);
+    /// assert_eq!(s.ceil_char_boundary(20), 10);
+    /// ```
+    #[inline]
+    pub fn ceil_char_boundary(&self, index: usize) -> usize {
+        let bytes = self.as_bytes();
+        if index >= bytes.len() {
+            return bytes.len();
+        }
+        let mut i = index;
+        while i < bytes.len() && !is_utf8_char_boundary(bytes[i]) {
+            i += 1;
+        }
+        i
+    }
+
+    /// Checks that `index`-th byte is the first byte in a UTF-8 code point sequence or the end of
+    /// the [`LeanString`].
     ///
-    /// s.reserve(100);
+    /// The start and end of the [`LeanString`] (when `index == self.len()`) are considered to be
+    /// boundaries. Returns `false` if `index` is greater than `self.len()`.
     ///
-    /// // Now we have a heap storage.
-    /// assert!(s.capacity() >= s.len() + 100);
-    /// assert!(s.is_heap_allocated());
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from("Löwe");
+    /// assert!(s.is_char_boundary(0));
+    /// assert!(s.is_char_boundary(1));
+    /// assert!(!s.is_char_boundary(2)); // inside 'ö'
+    /// assert!(s.is_char_boundary(5));
+    /// assert!(!s.is_char_boundary(6));
     /// ```
     #[inline]
-    pub fn reserve(&mut self, additional: usize) {
-        self.try_reserve(additional).unwrap_with_msg()
+    pub fn is_char_boundary(&self, index: usize) -> bool {
+        self.as_str().is_char_boundary(index)
     }
 
-    /// Fallible version of [`LeanString::reserve()`].
+    /// Returns the substring in `byte_range`, or [`None`] if either end of the range is not a
+    /// [`char`] boundary or is out of bounds.
+    ///
+    /// This is a non-panicking alternative to indexing with a range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from("Löwe");
+    /// assert_eq!(s.char_range(0..3), Some("Lö"));
+    /// assert_eq!(s.char_range(0..2), None);
+    /// assert_eq!(s.char_range(3..10), None);
+    /// ```
+    #[inline]
+    pub fn char_range(&self, byte_range: Range<usize>) -> Option<&str> {
+        self.as_str().get(byte_range)
+    }
+
+    /// Returns the substring in `range`, or [`None`] if either end of the range is not a [`char`]
+    /// boundary or is out of bounds.
+    ///
+    /// This is the same as [`str::get()`], but accepts any kind of range of byte positions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from("Löwe");
+    /// assert_eq!(s.get(..3), Some("Lö"));
+    /// assert_eq!(s.get(3..), Some("we"));
+    /// assert_eq!(s.get(..=1), None);
+    /// assert_eq!(s.get(3..10), None);
+    /// ```
+    #[inline]
+    pub fn get(&self, range: impl RangeBounds<usize>) -> Option<&str> {
+        self.as_str().get(to_range(range, self.len())?)
+    }
+
+    /// Returns a mutable substring in `range`, or [`None`] if either end of the range is not a
+    /// [`char`] boundary or is out of bounds.
+    ///
+    /// This is the same as [`str::get_mut()`], but accepts any kind of range of byte positions.
+    /// If the range is valid, the buffer is made unique and modifiable, i.e. a shared or static
+    /// buffer is copied, even if nothing is modified through the returned substring.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory when cloning the [`LeanString`]. If you want to
+    /// handle such a problem manually, use [`LeanString::try_get_mut()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::from("hello, world");
+    ///
+    /// s.get_mut(7..).unwrap().make_ascii_uppercase();
+    /// assert_eq!(s, "hello, WORLD");
+    ///
+    /// assert_eq!(s.get_mut(7..20), None);
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self, range: impl RangeBounds<usize>) -> Option<&mut str> {
+        self.try_get_mut(range).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::get_mut()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, but return an [`ReserveError`].
+    /// Otherwise it behaves the same as [`LeanString::get_mut()`]. An invalid range never
+    /// fails, because nothing is copied.
+    #[inline]
+    pub fn try_get_mut(
+        &mut self,
+        range: impl RangeBounds<usize>,
+    ) -> Result<Option<&mut str>, ReserveError> {
+        let range = match to_range(range, self.len()) {
+            Some(range) if self.as_str().get(range.clone()).is_some() => range,
+            _ => return Ok(None),
+        };
+        Ok(self.0.as_mut_str()?.get_mut(range))
+    }
+
+    /// Returns a mutable string slice of the whole [`LeanString`] only if it can be modified
+    /// without copying, otherwise returns [`None`].
+    ///
+    /// An inline buffer or a unique heap buffer is returned as is. A shared heap buffer (e.g. one
+    /// that has been cloned) or a static buffer would need a copy, so this returns [`None`]
+    /// instead. This never allocates, so callers can opportunistically mutate in place.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::from("a heap allocated string, longer than inline");
+    /// s.try_as_unique_mut().unwrap().make_ascii_uppercase();
+    /// assert_eq!(s, "A HEAP ALLOCATED STRING, LONGER THAN INLINE");
+    ///
+    /// let shared = s.clone();
+    /// assert_eq!(s.try_as_unique_mut(), None);
+    ///
+    /// drop(shared);
+    /// assert!(s.try_as_unique_mut().is_some());
+    ///
+    /// let mut s = LeanString::from_static_str("a static string, longer than inline");
+    /// assert_eq!(s.try_as_unique_mut(), None);
+    /// ```
+    #[inline]
+    pub fn try_as_unique_mut(&mut self) -> Option<&mut str> {
+        self.0.as_unique_mut_str()
+    }
+
+    /// Returns `true` if the first [`char`] of the [`LeanString`] is `ch`.
+    ///
+    /// This is the same as `self.starts_with(ch)`, but compares the UTF-8 bytes of `ch` directly
+    /// without going through the pattern matching machinery.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from("🦀 crab");
+    /// assert!(s.starts_with_char('🦀'));
+    /// assert!(!s.starts_with_char('c'));
+    ///
+    /// assert!(!LeanString::new().starts_with_char('a'));
+    /// ```
+    #[inline]
+    pub fn starts_with_char(&self, ch: char) -> bool {
+        self.as_bytes().starts_with(ch.encode_utf8(&mut [0; 4]).as_bytes())
+    }
+
+    /// Returns `true` if the last [`char`] of the [`LeanString`] is `ch`.
+    ///
+    /// This is the same as `self.ends_with(ch)`, but compares the UTF-8 bytes of `ch` directly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from("crab 🦀");
+    /// assert!(s.ends_with_char('🦀'));
+    /// assert!(!s.ends_with_char('b'));
+    ///
+    /// assert!(!LeanString::new().ends_with_char('a'));
+    /// ```
+    #[inline]
+    pub fn ends_with_char(&self, ch: char) -> bool {
+        self.as_bytes().ends_with(ch.encode_utf8(&mut [0; 4]).as_bytes())
+    }
+
+    /// Compares the [`LeanString`] with `other` ignoring ASCII case, without allocating
+    /// lowercased copies.
+    ///
+    /// The ordering is the same as comparing `self.to_ascii_lowercase()` and
+    /// `other.to_ascii_lowercase()`, so it is consistent with [`str::eq_ignore_ascii_case()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// use core::cmp::Ordering;
+    ///
+    /// let s = LeanString::from("Ferris");
+    /// assert_eq!(s.cmp_ignore_ascii_case("FERRIS"), Ordering::Equal);
+    /// assert_eq!(s.cmp_ignore_ascii_case("crab"), Ordering::Greater);
+    /// assert_eq!(s.cmp_ignore_ascii_case("Rust"), Ordering::Less);
+    /// ```
+    #[inline]
+    pub fn cmp_ignore_ascii_case(&self, other: &str) -> cmp::Ordering {
+        let lhs = self.as_bytes().iter().map(u8::to_ascii_lowercase);
+        let rhs = other.as_bytes().iter().map(u8::to_ascii_lowercase);
+        lhs.cmp(rhs)
+    }
+
+    /// Compares the [`LeanString`] with `other` in "natural" order, where runs of ASCII digits
+    /// are compared as numbers.
+    ///
+    /// For example, `"file2"` comes before `"file10"`, while they are in the opposite order with
+    /// [`Ord`]. Other characters are compared the same as [`Ord`]. Leading zeros don't change
+    /// the value of a number, and strings which are equal in this way (e.g. `"v01"` and `"v1"`)
+    /// are ordered by [`Ord`], so this is a total order consistent with [`Eq`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut files = ["file10.txt", "file2.txt", "file1.txt"].map(LeanString::from);
+    /// files.sort_by(|a, b| a.cmp_natural(b));
+    /// assert_eq!(files, ["file1.txt", "file2.txt", "file10.txt"]);
+    /// ```
+    pub fn cmp_natural(&self, other: &str) -> cmp::Ordering {
+        fn digits_end(bytes: &[u8], start: usize) -> usize {
+            bytes[start..]
+                .iter()
+                .position(|b| !b.is_ascii_digit())
+                .map_or(bytes.len(), |n| start + n)
+        }
+        fn trim_zeros(digits: &[u8]) -> &[u8] {
+            let zeros = digits.iter().take_while(|&&b| b == b'0').count();
+            &digits[zeros..]
+        }
+
+        let (lhs, rhs) = (self.as_bytes(), other.as_bytes());
+        let (mut i, mut j) = (0, 0);
+        while i < lhs.len() && j < rhs.len() {
+            if lhs[i].is_ascii_digit() && rhs[j].is_ascii_digit() {
+                let (i_end, j_end) = (digits_end(lhs, i), digits_end(rhs, j));
+                let (l, r) = (trim_zeros(&lhs[i..i_end]), trim_zeros(&rhs[j..j_end]));
+                // Without leading zeros, a longer number is a larger one.
+                let ordering = l.len().cmp(&r.len()).then_with(|| l.cmp(r));
+                if ordering.is_ne() {
+                    return ordering;
+                }
+                (i, j) = (i_end, j_end);
+            } else {
+                // Comparing UTF-8 bytes is the same as comparing chars.
+                let ordering = lhs[i].cmp(&rhs[j]);
+                if ordering.is_ne() {
+                    return ordering;
+                }
+                (i, j) = (i + 1, j + 1);
+            }
+        }
+        (lhs.len() - i).cmp(&(rhs.len() - j)).then_with(|| lhs.cmp(rhs))
+    }
+
+    /// Returns `true` if the [`LeanString`] contains `needle` as a substring.
+    ///
+    /// With the `memchr` feature, this uses `memchr::memmem` for multi-byte needles on
+    /// heap-sized strings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from("The quick brown fox jumps over the lazy dog");
+    /// assert!(s.contains_substr("fox"));
+    /// assert!(!s.contains_substr("cat"));
+    /// ```
+    #[inline]
+    pub fn contains_substr(&self, needle: &str) -> bool {
+        match_indices(self.as_str(), needle).next().is_some()
+    }
+
+    /// Returns `true` if the [`LeanString`] contains the ASCII `byte`.
+    ///
+    /// For an inline string, this compares a word at a time instead of byte by byte. Otherwise, it
+    /// uses `memchr` with the `memchr` feature, or [`str::contains()`] without it. A non-ASCII
+    /// byte is never contained as a char by itself, so this returns `false` for it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from("key=value");
+    /// assert!(s.contains_ascii_byte(b'='));
+    /// assert!(!s.contains_ascii_byte(b'&'));
+    /// ```
+    #[inline]
+    pub fn contains_ascii_byte(&self, byte: u8) -> bool {
+        if !byte.is_ascii() {
+            return false;
+        }
+        if let Some(found) = self.0.inline_contains_byte(byte) {
+            return found;
+        }
+        #[cfg(feature = "memchr")]
+        return features::memchr::contains_byte(self.as_bytes(), byte);
+        #[cfg(not(feature = "memchr"))]
+        return self.as_str().contains(byte as char);
+    }
+
+    /// Returns the number of non-overlapping matches of `pat` in the [`LeanString`].
+    ///
+    /// This is the same as `self.matches(pat).count()`. With the `memchr` feature, this uses
+    /// `memchr::memmem` for multi-byte patterns on heap-sized strings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from("abcXXXabcYYYabc");
+    /// assert_eq!(s.count_matches("abc"), 3);
+    ///
+    /// // matches don't overlap
+    /// assert_eq!(LeanString::from("aaaa").count_matches("aa"), 2);
+    /// ```
+    #[inline]
+    pub fn count_matches(&self, pat: &str) -> usize {
+        match_indices(self.as_str(), pat).count()
+    }
+
+    /// Replaces all matches of `from` with `to`, and returns the result as a new [`LeanString`].
+    ///
+    /// This is the same as [`str::replace()`], but returns a [`LeanString`] and accepts only a
+    /// string slice pattern. With the `memchr` feature, this uses `memchr::memmem` for
+    /// multi-byte patterns on heap-sized strings. The matches are counted first, so the result
+    /// is allocated once with the exact length.
+    ///
+    /// It is named differently so that [`str::replace()`], which accepts any pattern and returns
+    /// a `String`, is still reachable through `Deref`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory, or the length of the result is too large. If you
+    /// want to handle such a problem manually, use [`LeanString::try_replace_lean()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from("this is old");
+    ///
+    /// assert_eq!(s.replace_lean("old", "new"), "this is new");
+    /// assert_eq!(s.replace_lean("cookie monster", "little lamb"), s);
+    /// ```
+    #[inline]
+    pub fn replace_lean(&self, from: &str, to: &str) -> LeanString {
+        self.try_replace_lean(from, to).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::replace_lean()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, or the length of the result is
+    /// too large, but return an [`ReserveError`]. Otherwise it behaves the same as
+    /// [`LeanString::replace_lean()`].
+    #[inline]
+    pub fn try_replace_lean(&self, from: &str, to: &str) -> Result<LeanString, ReserveError> {
+        self.try_replacen_lean(from, to, usize::MAX)
+    }
+
+    /// Replaces the first `count` matches of `from` with `to`, and returns the result as a new
+    /// [`LeanString`].
+    ///
+    /// This is the same as [`str::replacen()`], but returns a [`LeanString`] and accepts only a
+    /// string slice pattern. Like [`LeanString::replace_lean()`], the result is allocated once with
+    /// the exact length.
+    ///
+    /// As with [`LeanString::replace_lean()`], [`str::replacen()`] is still reachable through
+    /// `Deref`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory, or the length of the result is too large. If you
+    /// want to handle such a problem manually, use [`LeanString::try_replacen_lean()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from("foo foo 123 foo");
+    ///
+    /// assert_eq!(s.replacen_lean("foo", "new", 2), "new new 123 foo");
+    /// assert_eq!(s.replacen_lean("foo", "new", 0), s);
+    /// ```
+    #[inline]
+    pub fn replacen_lean(&self, from: &str, to: &str, count: usize) -> LeanString {
+        self.try_replacen_lean(from, to, count).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::replacen_lean()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, or the length of the result is
+    /// too large, but return an [`ReserveError`]. Otherwise it behaves the same as
+    /// [`LeanString::replacen_lean()`].
+    pub fn try_replacen_lean(
+        &self,
+        from: &str,
+        to: &str,
+        count: usize,
+    ) -> Result<LeanString, ReserveError> {
+        let haystack = self.as_str();
+        let matches = match_indices(haystack, from).take(count).count();
+        if matches == 0 {
+            return Ok(self.clone());
+        }
+
+        // The matches don't overlap, so the removed bytes never exceed the length.
+        let kept = haystack.len() - matches * from.len();
+        let total = matches.checked_mul(to.len()).and_then(|added| kept.checked_add(added));
+        let mut result = LeanString::try_with_capacity(total.ok_or(ReserveError)?)?;
+
+        let mut last_end = 0;
+        for start in match_indices(haystack, from).take(matches) {
+            result.try_push_str(&haystack[last_end..start])?;
+            result.try_push_str(to)?;
+            last_end = start + from.len();
+        }
+        result.try_push_str(&haystack[last_end..])?;
+        Ok(result)
+    }
+
+    /// Replaces all occurrences of the [`char`] `from` with `to` in-place.
+    ///
+    /// When both [`char`]s have the same UTF-8 length (e.g. ASCII to ASCII), the bytes are
+    /// overwritten in-place, without any reallocation or shifting. Otherwise, the content is
+    /// rebuilt as with [`LeanString::replace_lean()`]. If there is no occurrence, a shared or static
+    /// buffer is not cloned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory, or the length of the result is too large. If you
+    /// want to handle such a problem manually, use [`LeanString::try_replace_char()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::from("2024/01/31");
+    /// s.replace_char('/', '-');
+    /// assert_eq!(s, "2024-01-31");
+    ///
+    /// s.replace_char('-', '→');
+    /// assert_eq!(s, "2024→01→31");
+    /// ```
+    #[inline]
+    pub fn replace_char(&mut self, from: char, to: char) {
+        self.try_replace_char(from, to).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::replace_char()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, or the length of the result is
+    /// too large, but return an [`ReserveError`]. Otherwise it behaves the same as
+    /// [`LeanString::replace_char()`].
+    pub fn try_replace_char(&mut self, from: char, to: char) -> Result<(), ReserveError> {
+        if from == to {
+            return Ok(());
+        }
+        let (mut from_buf, mut to_buf) = ([0; 4], [0; 4]);
+        let from = from.encode_utf8(&mut from_buf);
+        let to = to.encode_utf8(&mut to_buf);
+
+        if from.len() == to.len() {
+            self.0.replace_same_len(from.as_bytes(), to.as_bytes())
+        } else {
+            if !self.as_str().contains(&*from) {
+                return Ok(());
+            }
+            *self = self.try_replace_lean(from, to)?;
+            Ok(())
+        }
+    }
+
+    /// Returns an iterator over the substrings separated by `pat`, as owned [`LeanString`]s.
+    ///
+    /// The segments are the same as [`str::split()`]. Each of them is copied into a new
+    /// [`LeanString`], so short segments are inlined.
+    ///
+    /// # Panics
+    ///
+    /// The iterator panics if the system is out-of-memory when copying a segment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from("a,b,,c");
+    /// let parts: Vec<LeanString> = s.split_to_lean(',').collect();
+    /// assert_eq!(parts, ["a", "b", "", "c"]);
+    /// ```
+    #[inline]
+    pub fn split_to_lean(&self, pat: char) -> impl Iterator<Item = LeanString> + '_ {
+        // TODO: segments of a StaticBuffer are `'static` too, so they could reuse the static
+        // pointer without copying.
+        self.as_str().split(pat).map(LeanString::from)
+    }
+
+    /// Returns an iterator over the lines of the [`LeanString`], as owned [`LeanString`]s.
+    ///
+    /// The lines are the same as [`str::lines()`]: they are split on `'\n'`, a trailing `'\r'`
+    /// is stripped, and a trailing empty line is not returned. Each of them is copied into a new
+    /// [`LeanString`], and short lines are inlined.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory when copying a line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from("foo\r\nbar\n\nbaz\n");
+    /// let lines: Vec<LeanString> = s.lines_owned().collect();
+    /// assert_eq!(lines, ["foo", "bar", "", "baz"]);
+    /// ```
+    #[inline]
+    pub fn lines_owned(&self) -> impl Iterator<Item = LeanString> + '_ {
+        self.as_str().lines().map(LeanString::from)
+    }
+
+    /// Splits the [`LeanString`] on the first occurrence of `delimiter`, and returns the parts
+    /// before and after it as owned [`LeanString`]s.
+    ///
+    /// The parts are the same as [`str::split_once()`]. Short parts are inlined, and for a
+    /// static buffer, the parts borrow the same `&'static str` without copying.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory when copying a part.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from("key=value=more");
+    /// assert_eq!(s.split_once_owned("="), Some(("key".into(), "value=more".into())));
+    /// assert_eq!(s.split_once_owned(":"), None);
+    /// ```
+    pub fn split_once_owned(&self, delimiter: &str) -> Option<(LeanString, LeanString)> {
+        if let Some(text) = self.0.as_static_str() {
+            let (prefix, suffix) = text.split_once(delimiter)?;
+            return Some((
+                LeanString::from_static_str(prefix),
+                LeanString::from_static_str(suffix),
+            ));
+        }
+        let (prefix, suffix) = self.as_str().split_once(delimiter)?;
+        Some((LeanString::from(prefix), LeanString::from(suffix)))
+    }
+
+    /// Returns an iterator of [`u16`] over the [`LeanString`] encoded as UTF-16.
+    ///
+    /// This is the same as [`str::encode_utf16()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from("a𝄞");
+    /// let utf16: Vec<u16> = s.encode_utf16().collect();
+    /// assert_eq!(utf16, [0x0061, 0xD834, 0xDD1E]);
+    /// ```
+    #[inline]
+    pub fn encode_utf16(&self) -> impl Iterator<Item = u16> + '_ {
+        self.as_str().encode_utf16()
+    }
+
+    /// Returns the length of the [`LeanString`] in UTF-16 code units.
+    ///
+    /// This is the length of the string in JavaScript or Windows APIs, and it differs from
+    /// [`LeanString::len()`], which is in bytes. Characters outside of the Basic Multilingual
+    /// Plane are encoded as surrogate pairs, so they count as 2 units.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from("a€𝄞");
+    /// assert_eq!(s.len(), 1 + 3 + 4);
+    /// assert_eq!(s.len_utf16(), 1 + 1 + 2);
+    /// ```
+    #[inline]
+    pub fn len_utf16(&self) -> usize {
+        self.encode_utf16().count()
+    }
+
+    /// Converts the [`LeanString`] into an owning iterator over its [`char`]s.
+    ///
+    /// The iterator holds the [`LeanString`] itself, so it can outlive the original binding. It
+    /// also implements [`DoubleEndedIterator`], so it can be consumed from both ends.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from("abc");
+    /// let mut chars = s.into_chars();
+    ///
+    /// assert_eq!(chars.next(), Some('a'));
+    /// assert_eq!(chars.next_back(), Some('c'));
+    /// assert_eq!(chars.next(), Some('b'));
+    /// assert_eq!(chars.next_back(), None);
+    ///
+    /// let reversed: LeanString = LeanString::from("こんにちは").into_chars().rev().collect();
+    /// assert_eq!(reversed, "はちにんこ");
+    /// ```
+    #[inline]
+    pub fn into_chars(self) -> IntoChars {
+        IntoChars::new(self)
+    }
+
+    /// Converts the [`LeanString`] into an owning iterator over its [`char`]s and their byte
+    /// positions.
+    ///
+    /// This is the owning version of [`str::char_indices()`], so it can be returned from a
+    /// function which creates the [`LeanString`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::{IntoCharIndices, LeanString};
+    /// fn indexed(text: &str) -> IntoCharIndices {
+    ///     LeanString::from(text).into_char_indices()
+    /// }
+    ///
+    /// let mut indices = indexed("añb");
+    /// assert_eq!(indices.next(), Some((0, 'a')));
+    /// assert_eq!(indices.next_back(), Some((3, 'b')));
+    /// assert_eq!(indices.next(), Some((1, 'ñ')));
+    /// assert_eq!(indices.next(), None);
+    /// ```
+    #[inline]
+    pub fn into_char_indices(self) -> IntoCharIndices {
+        IntoCharIndices::new(self)
+    }
+
+    /// Converts the [`LeanString`] into an owning iterator over its bytes.
+    ///
+    /// Unlike `String::into_bytes().into_iter()`, the bytes are not copied into a [`Vec`], and
+    /// the buffer is released as soon as all bytes are consumed.
+    ///
+    /// [`Vec`]: alloc::vec::Vec
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut bytes = LeanString::from("añb").into_bytes_iter();
+    /// assert_eq!(bytes.len(), 4);
+    /// assert_eq!(bytes.next(), Some(b'a'));
+    /// assert_eq!(bytes.next_back(), Some(b'b'));
+    /// assert_eq!(bytes.collect::<Vec<u8>>(), "ñ".as_bytes());
+    /// ```
+    #[inline]
+    pub fn into_bytes_iter(self) -> IntoBytes {
+        IntoBytes::new(self)
+    }
+
+    /// Converts the [`LeanString`] into a [`String`], allocating the destination fallibly.
+    ///
+    /// This is the same as `String::from(self)`, but useful for code which needs to handle
+    /// out-of-memory without panicking.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ReserveError`] if the system is out-of-memory when allocating the [`String`].
+    /// No error occurs for an empty [`LeanString`], because an empty [`String`] never allocates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from("hello");
+    /// let string = s.try_into_string().unwrap();
+    /// assert_eq!(string, "hello");
+    /// ```
+    #[inline]
+    pub fn try_into_string(self) -> Result<String, ReserveError> {
+        let mut string = String::new();
+        string.try_reserve_exact(self.len()).map_err(|_| ReserveError)?;
+        string.push_str(self.as_str());
+        Ok(string)
+    }
+
+    /// Converts the [`LeanString`] into a [`Box<str>`], allocating the destination fallibly.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ReserveError`] if the system is out-of-memory when allocating the [`Box<str>`].
+    /// No error occurs for an empty [`LeanString`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from("hello");
+    /// let boxed = s.try_into_boxed_str().unwrap();
+    /// assert_eq!(&*boxed, "hello");
+    /// ```
+    #[inline]
+    pub fn try_into_boxed_str(self) -> Result<Box<str>, ReserveError> {
+        self.try_into_string().map(String::into_boxed_str)
+    }
+
+    /// Returns a [`Cow::Borrowed`] string slice containing the entire [`LeanString`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// # use std::borrow::Cow;
+    /// let s = LeanString::from("foo");
+    /// assert_eq!(s.as_cow(), Cow::Borrowed("foo"));
+    /// ```
+    #[inline]
+    pub fn as_cow(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self.as_str())
+    }
+
+    /// Converts the [`LeanString`] into a [`Cow<'static, str>`].
+    ///
+    /// If the [`LeanString`] is created from a `&'static str` (e.g. by
+    /// [`LeanString::from_static_str()`]) and has not been modified, this returns
+    /// [`Cow::Borrowed`] without allocation. Otherwise, this returns [`Cow::Owned`] with a copied
+    /// [`String`].
+    ///
+    /// [`Cow<'static, str>`]: Cow
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// # use std::borrow::Cow;
+    /// let s = LeanString::from_static_str("Long text but static lifetime");
+    /// assert!(matches!(s.into_cow(), Cow::Borrowed("Long text but static lifetime")));
+    ///
+    /// let s = LeanString::from("Long text but not static lifetime");
+    /// assert!(matches!(s.into_cow(), Cow::Owned(_)));
+    /// ```
+    #[inline]
+    pub fn into_cow(self) -> Cow<'static, str> {
+        match self.0.as_static_str() {
+            Some(s) => Cow::Borrowed(s),
+            None => Cow::Owned(self.as_str().into()),
+        }
+    }
+
+    /// Creates a new [`LeanString`] from a [`Cow<'static, str>`], keeping a borrowed string
+    /// static.
+    ///
+    /// For [`Cow::Borrowed`], this is the same as [`LeanString::from_static_str()`], so the
+    /// string is not copied. For [`Cow::Owned`], this is the same as `LeanString::from(String)`.
+    /// This is the inverse of [`LeanString::into_cow()`].
+    ///
+    /// The `impl From<Cow<'_, str>>` can't do this, because it can't tell whether the borrowed
+    /// string is `'static`, and an impl for `Cow<'static, str>` would overlap with it.
+    ///
+    /// [`Cow<'static, str>`]: Cow
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory when copying an owned string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// # use std::borrow::Cow;
+    /// let text: &'static str = "Long text but static lifetime";
+    /// let s = LeanString::from_static_cow(Cow::Borrowed(text));
+    /// assert_eq!(s.as_ptr(), text.as_ptr());
+    ///
+    /// let s = LeanString::from_static_cow(Cow::Owned(String::from("owned")));
+    /// assert_eq!(s, "owned");
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn from_static_cow(cow: Cow<'static, str>) -> Self {
+        match cow {
+            Cow::Borrowed(s) => LeanString::from_static_str(s),
+            Cow::Owned(s) => s.into(),
+        }
+    }
+
+    /// Converts the [`LeanString`] into a `&'static str`.
+    ///
+    /// If the [`LeanString`] is created from a `&'static str` (e.g. by
+    /// [`LeanString::from_static_str()`]) and has not been modified, the original slice is
+    /// returned without allocation. Otherwise, the contents are copied into a new allocation which
+    /// is leaked, and the [`LeanString`] itself is dropped as usual.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory. If you want to handle such a problem manually, use
+    /// [`LeanString::try_into_static_str()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let text: &'static str = "Long text but static lifetime";
+    /// let s = LeanString::from_static_str(text);
+    /// assert_eq!(s.into_static_str().as_ptr(), text.as_ptr());
+    /// ```
+    #[inline]
+    pub fn into_static_str(self) -> &'static str {
+        self.try_into_static_str().unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::into_static_str()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, but return an [`ReserveError`].
+    /// Otherwise it behaves the same as [`LeanString::into_static_str()`].
+    #[inline]
+    pub fn try_into_static_str(self) -> Result<&'static str, ReserveError> {
+        match self.0.as_static_str() {
+            Some(s) => Ok(s),
+            None => self.try_into_boxed_str().map(|boxed| &*Box::leak(boxed)),
+        }
+    }
+
+    /// Returns the remaining spare capacity of the [`LeanString`] as a slice of
+    /// `MaybeUninit<u8>`.
+    ///
+    /// The returned slice can be used to fill the [`LeanString`] with data (e.g. by reading from a
+    /// file) before marking the data as initialized using the [`LeanString::set_len()`] method.
+    ///
+    /// # Note
+    ///
+    /// This method clones the [`LeanString`] if it is not unique, and converts it to a modifiable
+    /// buffer if it is created from a `&'static str`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ReserveError`] if the system is out-of-memory when cloning the [`LeanString`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::with_capacity(32);
+    ///
+    /// let spare = s.spare_capacity_mut().unwrap();
+    /// for (dst, src) in spare.iter_mut().zip(b"Hello, world!") {
+    ///     dst.write(*src);
+    /// }
+    ///
+    /// // SAFETY: We have just initialized the first 13 bytes with valid UTF-8.
+    /// unsafe { s.set_len(13) };
+    /// assert_eq!(s, "Hello, world!");
+    /// ```
+    #[inline]
+    pub fn spare_capacity_mut(&mut self) -> Result<&mut [MaybeUninit<u8>], ReserveError> {
+        self.0.spare_capacity_mut()
+    }
+
+    /// Forces the length of the [`LeanString`] to `new_len`.
+    ///
+    /// This is a low-level operation that maintains none of the normal invariants of the type.
+    /// Normally changing the length of a [`LeanString`] is done using one of the safe operations
+    /// instead, such as [`LeanString::push_str()`] or [`LeanString::clear()`].
+    ///
+    /// # Safety
+    ///
+    /// - `new_len` must be less than or equal to [`LeanString::capacity()`].
+    /// - The bytes at `0..new_len` must be initialized, and must be valid UTF-8.
+    /// - The [`LeanString`] must be unique and modifiable, which is ensured by calling
+    ///   [`LeanString::spare_capacity_mut()`] without cloning the [`LeanString`] afterwards.
+    ///
+    /// In debug builds, this panics if `new_len` is greater than the capacity, or the bytes at
+    /// `0..new_len` are not valid UTF-8, to catch misuse early.
+    ///
+    /// # Examples
+    ///
+    /// See [`LeanString::spare_capacity_mut()`].
+    #[inline]
+    #[track_caller]
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        debug_assert!(
+            new_len <= self.capacity(),
+            "new_len is greater than capacity (new_len: {new_len}, capacity: {})",
+            self.capacity(),
+        );
+        // SAFETY: The caller must uphold the safety contract.
+        unsafe { self.0.set_len(new_len) }
+        debug_assert!(str::from_utf8(self.as_bytes()).is_ok(), "bytes are not valid UTF-8");
+    }
+
+    /// Reserves capacity for at least `additional` bytes more than the current length.
+    ///
+    /// # Note
+    ///
+    /// This method clones the [`LeanString`] if it is not unique.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the following conditions are met:
+    ///
+    /// - The system is out-of-memory.
+    /// - On 64-bit architecture, the `capacity` is greater than `2^56 - 1`.
+    /// - On 32-bit architecture, the `capacity` is greater than `2^24 - 2`.
+    ///
+    /// If you want to handle such a problem manually, use [`LeanString::try_reserve()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::new();
+    ///
+    /// // We have a inline strage on the stack.
+    /// assert_eq!(s.capacity(), size_of::<LeanString>());
+    /// assert!(!s.is_heap_allocated());
+    ///
+    /// s.reserve(100);
+    ///
+    /// // Now we have a heap storage.
+    /// assert!(s.capacity() >= s.len() + 100);
+    /// assert!(s.is_heap_allocated());
+    /// ```
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.try_reserve(additional).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::reserve()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, or the `capacity` is greater than
+    /// [`LeanString::MAX_HEAP_CAPACITY`] (`2^24 - 2` on 32-bit architecture), but return an
+    /// [`ReserveError`]. Otherwise it behaves the same as [`LeanString::reserve()`].
+    #[inline]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), ReserveError> {
+        self.0.reserve(additional)
+    }
+
+    /// Reserves capacity for at least `additional` bytes more than the current length, rounding
+    /// a new heap capacity up to a power of two.
+    ///
+    /// This is the same as [`LeanString::reserve()`], except that the capacity of a new or
+    /// reallocated heap buffer is the next power of two of `len + additional`, instead of the
+    /// amortized 1.5x growth. This can reduce the number of reallocations for strings that grow
+    /// in unpredictable increments, and plays nicely with size-class allocators. The capacity is
+    /// capped at the maximum capacity, which is not a power of two.
+    ///
+    /// Like [`LeanString::reserve()`], this does nothing if the current capacity is enough, and
+    /// the inline buffer is used if `len + additional` fits in it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory, or the capacity is too large. If you want to handle
+    /// such a problem manually, use [`LeanString::try_reserve_pow2()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::from("foo");
+    ///
+    /// s.reserve_pow2(100);
+    /// assert_eq!(s.capacity(), 128);
+    ///
+    /// s.reserve_pow2(100);
+    /// assert_eq!(s.capacity(), 128);
+    ///
+    /// s.reserve_pow2(126);
+    /// assert_eq!(s.capacity(), 256);
+    /// ```
+    #[inline]
+    pub fn reserve_pow2(&mut self, additional: usize) {
+        self.try_reserve_pow2(additional).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::reserve_pow2()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, or the capacity is too large, but
+    /// return an [`ReserveError`]. Otherwise it behaves the same as
+    /// [`LeanString::reserve_pow2()`].
+    #[inline]
+    pub fn try_reserve_pow2(&mut self, additional: usize) -> Result<(), ReserveError> {
+        self.0.reserve_pow2(additional)
+    }
+
+    /// Ensures that the capacity of the [`LeanString`] is at least `min_capacity` bytes.
+    ///
+    /// Unlike [`LeanString::reserve()`], `min_capacity` is the total capacity, not the additional
+    /// bytes to the current length. This is useful when you know the final size.
+    ///
+    /// # Note
+    ///
+    /// This method does nothing if the current capacity is already enough, so it doesn't
+    /// make the buffer unique or convert a static buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory, or the `min_capacity` is too large. If you want to
+    /// handle such a problem manually, use [`LeanString::try_reserve_total()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::from("foo");
+    ///
+    /// s.reserve_total(100);
+    /// assert!(s.capacity() >= 100);
+    ///
+    /// let cap = s.capacity();
+    /// s.reserve_total(50);
+    /// assert_eq!(s.capacity(), cap);
+    /// ```
+    #[inline]
+    pub fn reserve_total(&mut self, min_capacity: usize) {
+        self.try_reserve_total(min_capacity).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::reserve_total()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, or the `min_capacity` is too
+    /// large, but return an [`ReserveError`]. Otherwise it behaves the same as
+    /// [`LeanString::reserve_total()`].
+    #[inline]
+    pub fn try_reserve_total(&mut self, min_capacity: usize) -> Result<(), ReserveError> {
+        if self.capacity() >= min_capacity {
+            return Ok(());
+        }
+        self.0.reserve(min_capacity - self.len())
+    }
+
+    /// Reserves capacity for pushing pieces of the given byte lengths.
+    ///
+    /// This is the same as `self.reserve(pieces.into_iter().sum())`, and is useful when you
+    /// know the size of each piece before you have the strings themselves.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory, or the total length is too large. If you want to
+    /// handle such a problem manually, use [`LeanString::try_reserve_for()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let words = ["lorem", "ipsum", "dolor", "sit", "amet"];
+    ///
+    /// let mut s = LeanString::new();
+    /// s.reserve_for(words.iter().map(|w| w.len()));
+    /// let capacity = s.capacity();
+    ///
+    /// for word in words {
+    ///     s.push_str(word);
+    /// }
+    /// assert_eq!(s, "loremipsumdolorsitamet");
+    /// assert_eq!(s.capacity(), capacity);
+    /// ```
+    #[inline]
+    pub fn reserve_for(&mut self, pieces: impl IntoIterator<Item = usize>) {
+        self.try_reserve_for(pieces).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::reserve_for()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, or the total length is too large,
+    /// but return an [`ReserveError`]. Otherwise it behaves the same as
+    /// [`LeanString::reserve_for()`].
+    #[inline]
+    pub fn try_reserve_for(
+        &mut self,
+        pieces: impl IntoIterator<Item = usize>,
+    ) -> Result<(), ReserveError> {
+        let total = pieces.into_iter().try_fold(0usize, |acc, len| acc.checked_add(len));
+        self.try_reserve(total.ok_or(ReserveError)?)
+    }
+
+    /// Shrinks the capacity of the [`LeanString`] to match its length.
+    ///
+    /// The resulting capacity is always greater than `size_of::<LeanString>()` bytes because
+    /// [`LeanString`] has inline (on the stack) storage.
+    ///
+    /// # Note
+    ///
+    /// This method clones the [`LeanString`] if it is not unique and its capacity is greater than
+    /// its length.
+    ///
+    /// # Panics
+    ///
+    /// Panics if cloning the [`LeanString`] fails due to the system being out-of-memory. If you
+    /// want to handle such a problem manually, use [`LeanString::try_shrink_to_fit()`].
+    ///
+    /// # Examples
+    ///
+    /// ## short string
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::from("foo");
+    ///
+    /// s.reserve(100);
+    /// assert_eq!(s.capacity(), 3 + 100);
+    ///
+    /// s.shrink_to_fit();
+    /// assert_eq!(s.capacity(), size_of::<LeanString>());
+    /// ```
+    ///
+    /// ## long string
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::from("This is a text the length is more than 16 bytes");
+    ///
+    /// s.reserve(100);
+    /// assert!(s.capacity() > 16 + 100);
+    ///
+    /// s.shrink_to_fit();
+    /// assert_eq!(s.capacity(), s.len());
+    /// ```
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        self.try_shrink_to_fit().unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::shrink_to_fit()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, or the `capacity` is too large, but
+    /// return an [`ReserveError`]. Otherwise it behaves the same as [`LeanString::shrink_to_fit()`].
+    #[inline]
+    pub fn try_shrink_to_fit(&mut self) -> Result<(), ReserveError> {
+        self.0.shrink_to(0)
+    }
+
+    /// Shrinks the capacity of the [`LeanString`] with a lower bound.
+    ///
+    /// The resulting capacity is always greater than `size_of::<LeanString>()` bytes because the
+    /// [`LeanString`] has inline (on the stack) storage.
+    ///
+    /// # Note
+    ///
+    /// This method clones the [`LeanString`] if it is not unique and its capacity will be changed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if cloning the [`LeanString`] fails due to the system being out-of-memory. If you
+    /// want to handle such a problem manually, use [`LeanString::try_shrink_to()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::with_capacity(100);
+    /// assert_eq!(s.capacity(), 100);
+    ///
+    /// // if the capacity was already bigger than the argument and unique, the call is no-op.
+    /// s.shrink_to(100);
+    /// assert_eq!(s.capacity(), 100);
+    ///
+    /// s.shrink_to(50);
+    /// assert_eq!(s.capacity(), 50);
+    ///
+    /// // if the string can be inlined, it is
+    /// s.shrink_to(8);
+    /// assert_eq!(s.capacity(), size_of::<LeanString>());
+    /// ```
+    #[inline]
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        self.try_shrink_to(min_capacity).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::shrink_to()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, or the `capacity` is too large, but
+    /// return an [`ReserveError`]. Otherwise it behaves the same as [`LeanString::shrink_to()`].
+    #[inline]
+    pub fn try_shrink_to(&mut self, min_capacity: usize) -> Result<(), ReserveError> {
+        self.0.shrink_to(min_capacity)
+    }
+
+    /// Converts the [`LeanString`] created from a `&'static str` into inline storage, if it is
+    /// short enough.
+    ///
+    /// A [`LeanString`] created by [`LeanString::from_static_str()`] keeps referring to the static
+    /// string even after it is shortened (e.g. by [`LeanString::pop()`]), and its capacity is the
+    /// same as its length. This method copies such a string into the inline storage, so that it
+    /// has the inline capacity for growth. Otherwise, this method does nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::from_static_str("This is a static string, not inlined");
+    /// while s.len() > 4 {
+    ///     s.pop();
+    /// }
+    /// assert_eq!(s.capacity(), 4);
+    ///
+    /// s.compact();
+    /// assert_eq!(s, "This");
+    /// assert_eq!(s.capacity(), size_of::<LeanString>());
+    /// ```
+    #[inline]
+    pub fn compact(&mut self) {
+        self.0.compact()
+    }
+
+    /// Appends the given [`char`] to the end of the [`LeanString`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory. If you want to handle such a problem manually, use
+    /// [`LeanString::try_push()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::new();
+    /// s.push('f');
+    /// s.push('o');
+    /// s.push('o');
+    /// assert_eq!("foo", s);
+    /// ```
+    #[inline]
+    pub fn push(&mut self, ch: char) {
+        self.try_push(ch).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::push()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, or the `capacity` is too large, but
+    /// return an [`ReserveError`]. Otherwise it behaves the same as [`LeanString::push()`].
+    #[inline]
+    pub fn try_push(&mut self, ch: char) -> Result<(), ReserveError> {
+        self.0.push_char(ch)
+    }
+
+    /// Appends the given [`char`] only if the result fits inline, otherwise returns it back.
+    ///
+    /// This never allocates. If the [`LeanString`] is heap allocated, or the result would be
+    /// longer than [`LeanString::INLINE_CAPACITY`], the [`LeanString`] is unchanged and
+    /// `Err(ch)` is returned, so that the caller can route the [`char`] elsewhere.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::from("a".repeat(LeanString::INLINE_CAPACITY - 1));
+    ///
+    /// assert_eq!(s.try_push_inline('🦀'), Err('🦀'));
+    /// assert_eq!(s.try_push_inline('b'), Ok(()));
+    /// assert_eq!(s.try_push_inline('c'), Err('c'));
+    /// assert!(!s.is_heap_allocated());
+    /// ```
+    #[inline]
+    pub fn try_push_inline(&mut self, ch: char) -> Result<(), char> {
+        if self.is_heap_allocated() || self.len() + ch.len_utf8() > MAX_INLINE_SIZE {
+            return Err(ch);
+        }
+        // The result fits inline, so this never allocates.
+        self.0.push_char(ch).map_err(|_| ch)
+    }
+
+    /// Removes the last character from the [`LeanString`] and returns it.
+    /// If the [`LeanString`] is empty, `None` is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if cloning the [`LeanString`] fails due to the system being out-of-memory. If you
+    /// want to handle such a problem manually, use [`LeanString::try_pop()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::from("abč");
+    ///
+    /// assert_eq!(s.pop(), Some('č'));
+    /// assert_eq!(s.pop(), Some('b'));
+    /// assert_eq!(s.pop(), Some('a'));
+    ///
+    /// assert_eq!(s.pop(), None);
+    /// ```
+    #[inline]
+    pub fn pop(&mut self) -> Option<char> {
+        self.try_pop().unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::pop()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, or the `capacity` is too large, but
+    /// return an [`ReserveError`]. Otherwise it behaves the same as [`LeanString::pop()`].
+    #[inline]
+    pub fn try_pop(&mut self) -> Result<Option<char>, ReserveError> {
+        self.0.pop()
+    }
+
+    /// Removes the last `n` characters from the [`LeanString`] and returns them as a new
+    /// [`LeanString`].
+    ///
+    /// If the [`LeanString`] has fewer than `n` characters, all of them are removed. Unlike
+    /// calling [`LeanString::pop()`] `n` times, the suffix is copied at once and `self` is
+    /// truncated only once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory when copying the suffix or cloning the
+    /// [`LeanString`]. If you want to handle such a problem manually, use
+    /// [`LeanString::try_pop_n()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::from("abčd");
+    ///
+    /// assert_eq!(s.pop_n(2), "čd");
+    /// assert_eq!(s, "ab");
+    ///
+    /// assert_eq!(s.pop_n(10), "ab");
+    /// assert_eq!(s, "");
+    /// ```
+    #[inline]
+    pub fn pop_n(&mut self, n: usize) -> LeanString {
+        self.try_pop_n(n).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::pop_n()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, but return an [`ReserveError`].
+    /// Otherwise it behaves the same as [`LeanString::pop_n()`]. On error, `self` is unchanged.
+    pub fn try_pop_n(&mut self, n: usize) -> Result<LeanString, ReserveError> {
+        let new_len = match n.checked_sub(1) {
+            None => self.len(),
+            Some(nth) => self.as_str().char_indices().nth_back(nth).map_or(0, |(i, _)| i),
+        };
+        let suffix = LeanString(Repr::from_str(&self.as_str()[new_len..])?);
+        self.0.truncate(new_len)?;
+        Ok(suffix)
+    }
+
+    /// Splits the [`LeanString`] into two at the given byte index.
+    ///
+    /// Returns a new [`LeanString`] containing the bytes `[at, len)`, and `self` is left with the
+    /// bytes `[0, at)`. The capacity of `self` is not changed. For a static buffer, the returned
+    /// [`LeanString`] borrows the same `&'static str` without copying.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the following conditions:
+    ///
+    /// 1. `at` is larger than the [`LeanString`]'s length, or if it does not lie on a [`char`]
+    ///    boundary.
+    /// 2. The system is out-of-memory when copying the suffix or cloning the [`LeanString`].
+    ///
+    /// For 2, if you want to handle such a problem manually, use [`LeanString::try_split_off()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut hello = LeanString::from("Hello, World!");
+    /// let world = hello.split_off(7);
+    /// assert_eq!(hello, "Hello, ");
+    /// assert_eq!(world, "World!");
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn split_off(&mut self, at: usize) -> LeanString {
+        self.try_split_off(at).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::split_off()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, but return an [`ReserveError`].
+    /// Otherwise it behaves the same as [`LeanString::split_off()`]. On error, `self` is
+    /// unchanged.
+    #[track_caller]
+    pub fn try_split_off(&mut self, at: usize) -> Result<LeanString, ReserveError> {
+        assert!(
+            self.is_char_boundary(at),
+            "index is not a char boundary or out of bounds (index: {at})",
+        );
+        let suffix = match self.0.as_static_str() {
+            Some(text) => LeanString::from_static_str(&text[at..]),
+            None => LeanString(Repr::from_str(&self.as_str()[at..])?),
+        };
+        self.0.truncate(at)?;
+        Ok(suffix)
+    }
+
+    /// Removes the tail of the [`LeanString`] starting at the byte index `idx`, and returns it.
+    ///
+    /// This is an alias of [`LeanString::split_off()`], named for extracting a tail. See its
+    /// documentation for more. The fallible version is [`LeanString::try_split_off()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than the [`LeanString`]'s length, or if it does not lie on a
+    /// [`char`] boundary, or the system is out-of-memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut path = LeanString::from("archive.tar.gz");
+    /// assert_eq!(path.take_suffix_from(7), ".tar.gz");
+    /// assert_eq!(path, "archive");
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn take_suffix_from(&mut self, idx: usize) -> LeanString {
+        self.split_off(idx)
+    }
+
+    /// Makes the [`LeanString`] fit within `max_bytes` bytes, and returns whether it already did.
+    ///
+    /// If the length of the [`LeanString`] is greater than `max_bytes`, it is truncated to the
+    /// nearest [`char`] boundary not above `max_bytes` and `false` is returned. Otherwise, the
+    /// [`LeanString`] is unchanged and `true` is returned. This is useful for enforcing a size
+    /// limit of a field.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory when cloning the [`LeanString`]. If you want to
+    /// handle such a problem manually, use [`LeanString::try_fit_within()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::from("Löwe");
+    /// assert!(s.fit_within(5));
+    /// assert_eq!(s, "Löwe");
+    ///
+    /// // 'ö' is 2 bytes, and it is not split.
+    /// assert!(!s.fit_within(2));
+    /// assert_eq!(s, "L");
+    /// ```
+    #[inline]
+    pub fn fit_within(&mut self, max_bytes: usize) -> bool {
+        self.try_fit_within(max_bytes).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::fit_within()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, but return an [`ReserveError`].
+    /// Otherwise it behaves the same as [`LeanString::fit_within()`].
+    #[inline]
+    pub fn try_fit_within(&mut self, max_bytes: usize) -> Result<bool, ReserveError> {
+        if self.len() <= max_bytes {
+            return Ok(true);
+        }
+        self.0.truncate(self.floor_char_boundary(max_bytes))?;
+        Ok(false)
+    }
+
+    /// Appends a given string slice onto the end of this [`LeanString`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if cloning the [`LeanString`] fails due to the system being out-of-memory. If you
+    /// want to handle such a problem manually, use [`LeanString::try_push_str()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::from("foo");
+    ///
+    /// s.push_str("bar");
+    ///
+    /// assert_eq!("foobar", s);
+    /// ```
+    #[inline]
+    pub fn push_str(&mut self, string: &str) {
+        self.try_push_str(string).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::push_str()`].
     ///
     /// This method won't panic if the system is out-of-memory, or the `capacity` is too large, but
-    /// return an [`ReserveError`]. Otherwise it behaves the same as [`LeanString::reserve()`].
+    /// return an [`ReserveError`]. Otherwise it behaves the same as [`LeanString::push_str()`].
+    #[inline]
+    pub fn try_push_str(&mut self, string: &str) -> Result<(), ReserveError> {
+        self.0.push_str(string)
+    }
+
+    /// Appends a given [`Cow<'_, str>`] onto the end of this [`LeanString`].
+    ///
+    /// The bytes are always copied, also for [`Cow::Owned`]. The buffer of a [`String`] can't be
+    /// adopted, because a heap allocated [`LeanString`] stores its reference count and capacity
+    /// in front of the text, in the same allocation.
+    ///
+    /// For [`Cow::Borrowed`], this is the same as [`LeanString::push_str()`]. For [`Cow::Owned`],
+    /// if the [`LeanString`] is empty and can't hold the string without reallocation, the new
+    /// buffer is allocated with the exact length, like `LeanString::from(String)`, instead of the
+    /// amortized growth of [`LeanString::push_str()`].
+    ///
+    /// [`Cow<'_, str>`]: Cow
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory, or the length is too large. If you want to handle
+    /// such a problem manually, use [`LeanString::try_push_cow()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// # use std::borrow::Cow;
+    /// let mut s = LeanString::new();
+    ///
+    /// s.push_cow(Cow::Owned(String::from("foo")));
+    /// s.push_cow(Cow::Borrowed("bar"));
+    ///
+    /// assert_eq!(s, "foobar");
+    /// ```
+    #[inline]
+    pub fn push_cow(&mut self, cow: Cow<'_, str>) {
+        self.try_push_cow(cow).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::push_cow()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, or the length is too large, but
+    /// return an [`ReserveError`]. Otherwise it behaves the same as [`LeanString::push_cow()`].
+    #[inline]
+    pub fn try_push_cow(&mut self, cow: Cow<'_, str>) -> Result<(), ReserveError> {
+        match cow {
+            Cow::Owned(string) if self.is_empty() && string.len() > self.capacity() => {
+                self.0.replace_inner(Repr::from_str(&string)?);
+                Ok(())
+            }
+            cow => self.try_push_str(&cow),
+        }
+    }
+
+    /// Appends the decimal representation of the signed integer `value` onto the end of this
+    /// [`LeanString`].
+    ///
+    /// This formats into a stack buffer with `itoa`, which is faster than
+    /// `write!(s, "{value}")` because it doesn't go through the [`core::fmt`] machinery.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory, or the length is too large. If you want to handle
+    /// such a problem manually, use [`LeanString::try_push_int()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::from("x=");
+    /// s.push_int(-42);
+    /// s.push(',');
+    /// s.push_int(i64::MIN);
+    /// assert_eq!(s, "x=-42,-9223372036854775808");
+    /// ```
+    #[inline]
+    pub fn push_int(&mut self, value: i64) {
+        self.try_push_int(value).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::push_int()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, or the length is too large, but
+    /// return an [`ReserveError`]. Otherwise it behaves the same as [`LeanString::push_int()`].
+    #[inline]
+    pub fn try_push_int(&mut self, value: i64) -> Result<(), ReserveError> {
+        self.try_push_str(itoa::Buffer::new().format(value))
+    }
+
+    /// Appends the decimal representation of the unsigned integer `value` onto the end of this
+    /// [`LeanString`].
+    ///
+    /// This is the unsigned version of [`LeanString::push_int()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory, or the length is too large. If you want to handle
+    /// such a problem manually, use [`LeanString::try_push_uint()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::from("max=");
+    /// s.push_uint(u64::MAX);
+    /// assert_eq!(s, "max=18446744073709551615");
+    /// ```
+    #[inline]
+    pub fn push_uint(&mut self, value: u64) {
+        self.try_push_uint(value).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::push_uint()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, or the length is too large, but
+    /// return an [`ReserveError`]. Otherwise it behaves the same as [`LeanString::push_uint()`].
+    #[inline]
+    pub fn try_push_uint(&mut self, value: u64) -> Result<(), ReserveError> {
+        self.try_push_str(itoa::Buffer::new().format(value))
+    }
+
+    /// Appends `value` with `precision` digits after the decimal point onto the end of this
+    /// [`LeanString`].
+    ///
+    /// The output is the same as `format!("{value:.precision$}")`, i.e. correctly rounded, and
+    /// NaN and infinities are appended as `"NaN"`, `"inf"` and `"-inf"`. The digits are computed
+    /// by an exact formatter of this crate instead of the [`core::fmt`] machinery, and written
+    /// directly into the [`LeanString`], without a temporary [`String`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory, or the length is too large. If you want to handle
+    /// such a problem manually, use [`LeanString::try_push_f64()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::from("pi=");
+    /// s.push_f64(core::f64::consts::PI, 3);
+    /// assert_eq!(s, "pi=3.142");
+    ///
+    /// s.push(' ');
+    /// s.push_f64(f64::NEG_INFINITY, 3);
+    /// assert_eq!(s, "pi=3.142 -inf");
+    /// ```
+    #[inline]
+    pub fn push_f64(&mut self, value: f64, precision: usize) {
+        self.try_push_f64(value, precision).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::push_f64()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, or the length is too large, but
+    /// return an [`ReserveError`]. Otherwise it behaves the same as [`LeanString::push_f64()`].
+    /// On error, `self` is unchanged.
+    pub fn try_push_f64(&mut self, value: f64, precision: usize) -> Result<(), ReserveError> {
+        if value.is_nan() {
+            return self.try_push_str("NaN");
+        }
+        if value.is_infinite() {
+            return self.try_push_str(if value > 0.0 { "inf" } else { "-inf" });
+        }
+        let decimal = FixedDecimal::new(value, precision);
+        // After reserving, pushing the parts never fails, so `self` is unchanged on error.
+        self.try_reserve(decimal.len().ok_or(ReserveError)?)?;
+        decimal.write(|part| self.try_push_str(part))
+    }
+
+    /// Appends a given string slice onto the end of the [`LeanString`], and returns it.
+    ///
+    /// This is a chainable version of [`LeanString::push_str()`] for fluent construction.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory, or the length is too large.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::new().with_pushed_str("foo").with_pushed('-').with_pushed_str("bar");
+    /// assert_eq!(s, "foo-bar");
+    /// ```
+    #[inline]
+    pub fn with_pushed_str(mut self, string: &str) -> Self {
+        self.push_str(string);
+        self
+    }
+
+    /// Appends the given [`char`] to the end of the [`LeanString`], and returns it.
+    ///
+    /// This is a chainable version of [`LeanString::push()`] for fluent construction.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory, or the length is too large.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from("ab").with_pushed('c').with_pushed('🦀');
+    /// assert_eq!(s, "abc🦀");
+    /// ```
+    #[inline]
+    pub fn with_pushed(mut self, ch: char) -> Self {
+        self.push(ch);
+        self
+    }
+
+    /// Appends as many whole [`char`]s of `string` as keep the length of the [`LeanString`] less
+    /// than or equal to `max_len`, and returns the number of bytes actually appended.
+    ///
+    /// This is useful for bounded sinks, such as truncating log lines. A [`char`] is never split,
+    /// so fewer bytes than the remaining room may be appended.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory. If you want to handle such a problem manually, use
+    /// [`LeanString::try_push_str_bounded()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::from("log: ");
+    ///
+    /// // 'é' is 2 bytes, so it doesn't fit in the 1 byte room.
+    /// assert_eq!(s.push_str_bounded("café au lait", 9), 3);
+    /// assert_eq!(s, "log: caf");
+    ///
+    /// assert_eq!(s.push_str_bounded("!", 9), 1);
+    /// assert_eq!(s.push_str_bounded("!", 9), 0);
+    /// assert_eq!(s, "log: caf!");
+    /// ```
+    #[inline]
+    pub fn push_str_bounded(&mut self, string: &str, max_len: usize) -> usize {
+        self.try_push_str_bounded(string, max_len).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::push_str_bounded()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, but return an [`ReserveError`].
+    /// Otherwise it behaves the same as [`LeanString::push_str_bounded()`].
+    #[inline]
+    pub fn try_push_str_bounded(
+        &mut self,
+        string: &str,
+        max_len: usize,
+    ) -> Result<usize, ReserveError> {
+        let room = max_len.saturating_sub(self.len());
+        let mut end = room.min(string.len());
+        while !string.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.0.push_str(&string[..end])?;
+        Ok(end)
+    }
+
+    /// Appends `bytes` to the end of the [`LeanString`] if the whole slice is valid UTF-8.
+    ///
+    /// The slice is validated before anything is appended, so on error the [`LeanString`] is
+    /// unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PushBytesError::Utf8`] if `bytes` is not valid UTF-8, or
+    /// [`PushBytesError::Reserve`] if the system is out-of-memory, or the length is too large.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::{LeanString, PushBytesError};
+    /// let mut s = LeanString::from("foo");
+    ///
+    /// assert!(s.push_bytes_checked(b"bar").is_ok());
+    /// assert_eq!(s, "foobar");
+    ///
+    /// let err = s.push_bytes_checked(b"baz\xFF").unwrap_err();
+    /// assert!(matches!(err, PushBytesError::Utf8(_)));
+    /// assert_eq!(s, "foobar");
+    /// ```
+    #[inline]
+    pub fn push_bytes_checked(&mut self, bytes: &[u8]) -> Result<(), PushBytesError> {
+        let string = str::from_utf8(bytes)?;
+        self.0.push_str(string)?;
+        Ok(())
+    }
+
+    /// Removes a [`char`] from the [`LeanString`] at a byte position and returns it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the following conditions:
+    ///
+    /// 1. `idx` is larger than or equal tothe [`LeanString`]'s length, or if it does not lie on a [`char`]
+    /// 2. The system is out-of-memory when cloning the [`LeanString`].
+    ///
+    /// For 2, if you want to handle such a problem manually, use [`LeanString::try_remove()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::from("Hello 世界");
+    ///
+    /// assert_eq!(s.remove(6), '世');
+    /// assert_eq!(s.remove(1), 'e');
+    ///
+    /// assert_eq!(s, "Hllo 界");
+    /// ```
+    /// ## Past total length:
+    ///
+    /// ```should_panic
+    /// # use lean_string::LeanString;
+    /// let mut c = LeanString::from("hello there!");
+    /// c.remove(12);
+    /// ```
+    ///
+    /// ## Not on char boundary:
+    ///
+    /// ```should_panic
+    /// # use lean_string::LeanString;
+    /// let mut c = LeanString::from("🦄");
+    /// c.remove(1);
+    /// ```
     #[inline]
-    pub fn try_reserve(&mut self, additional: usize) -> Result<(), ReserveError> {
-        self.0.reserve(additional)
+    pub fn remove(&mut self, idx: usize) -> char {
+        self.try_remove(idx).unwrap_with_msg()
     }
 
-    /// Shrinks the capacity of the [`LeanString`] to match its length.
-    ///
-    /// The resulting capacity is always greater than `2 * size_of::<usize>()` bytes because
-    /// [`LeanString`] has inline (on the stack) storage.
+    /// Fallible version of [`LeanString::remove()`].
     ///
-    /// # Note
+    /// This method won't panic if the system is out-of-memory, but return an [`ReserveError`].
+    /// Otherwise it behaves the same as [`LeanString::remove()`].
+    #[inline]
+    pub fn try_remove(&mut self, idx: usize) -> Result<char, ReserveError> {
+        self.0.remove(idx)
+    }
+
+    /// Retains only the characters specified by the `predicate`.
     ///
-    /// This method clones the [`LeanString`] if it is not unique and its capacity is greater than
-    /// its length.
+    /// If the `predicate` returns `true`, the character is kept, otherwise it is removed.
     ///
     /// # Panics
     ///
-    /// Panics if cloning the [`LeanString`] fails due to the system being out-of-memory. If you
-    /// want to handle such a problem manually, use [`LeanString::try_shrink_to_fit()`].
+    /// Panics if the system is out-of-memory when cloning the [`LeanString`]. If you want to
+    /// handle such a problem manually, use [`LeanString::try_retain()`].
     ///
     /// # Examples
     ///
-    /// ## short string
-    ///
     /// ```
     /// # use lean_string::LeanString;
-    /// let mut s = LeanString::from("foo");
+    /// let mut s = LeanString::from("äb𝄞d€");
     ///
-    /// s.reserve(100);
-    /// assert_eq!(s.capacity(), 3 + 100);
+    /// let keep = [false, true, true, false, true];
+    /// let mut iter = keep.iter();
+    /// s.retain(|_| *iter.next().unwrap());
     ///
-    /// s.shrink_to_fit();
-    /// assert_eq!(s.capacity(), 2 * size_of::<usize>());
+    /// assert_eq!(s, "b𝄞€");
     /// ```
+    #[inline]
+    pub fn retain(&mut self, predicate: impl FnMut(char) -> bool) {
+        self.try_retain(predicate).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::retain()`].
     ///
-    /// ## long string
+    /// This method won't panic if the system is out-of-memory, but return an [`ReserveError`].
+    #[inline]
+    pub fn try_retain(
+        &mut self,
+        mut predicate: impl FnMut(char) -> bool,
+    ) -> Result<(), ReserveError> {
+        self.0.retain(|_, ch| predicate(ch)).map(|_| ())
+    }
+
+    /// Retains only the characters specified by the `predicate`, and returns whether any character
+    /// was removed.
+    ///
+    /// This is the same as [`LeanString::retain()`], but useful when you need to know whether the
+    /// contents has changed (e.g. to invalidate caches).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory when cloning the [`LeanString`]. If you want to
+    /// handle such a problem manually, use [`LeanString::try_retain_reporting()`].
+    ///
+    /// # Examples
     ///
     /// ```
     /// # use lean_string::LeanString;
-    /// let mut s = LeanString::from("This is a text the length is more than 16 bytes");
+    /// let mut s = LeanString::from("a1b2c3");
     ///
-    /// s.reserve(100);
-    /// assert!(s.capacity() > 16 + 100);
+    /// assert!(s.retain_reporting(|c| c.is_alphabetic()));
+    /// assert_eq!(s, "abc");
     ///
-    /// s.shrink_to_fit();
-    /// assert_eq!(s.capacity(), s.len());
+    /// assert!(!s.retain_reporting(|c| c.is_alphabetic()));
+    /// assert_eq!(s, "abc");
     /// ```
     #[inline]
-    pub fn shrink_to_fit(&mut self) {
-        self.try_shrink_to_fit().unwrap_with_msg()
+    pub fn retain_reporting(&mut self, predicate: impl FnMut(char) -> bool) -> bool {
+        self.try_retain_reporting(predicate).unwrap_with_msg()
     }
 
-    /// Fallible version of [`LeanString::shrink_to_fit()`].
+    /// Fallible version of [`LeanString::retain_reporting()`].
     ///
-    /// This method won't panic if the system is out-of-memory, or the `capacity` is too large, but
-    /// return an [`ReserveError`]. Otherwise it behaves the same as [`LeanString::shrink_to_fit()`].
+    /// This method won't panic if the system is out-of-memory, but return an [`ReserveError`].
     #[inline]
-    pub fn try_shrink_to_fit(&mut self) -> Result<(), ReserveError> {
-        self.0.shrink_to(0)
+    pub fn try_retain_reporting(
+        &mut self,
+        mut predicate: impl FnMut(char) -> bool,
+    ) -> Result<bool, ReserveError> {
+        self.0.retain(|_, ch| predicate(ch))
     }
 
-    /// Shrinks the capacity of the [`LeanString`] with a lower bound.
-    ///
-    /// The resulting capacity is always greater than `2 * size_of::<usize>()` bytes because the
-    /// [`LeanString`] has inline (on the stack) storage.
-    ///
-    /// # Note
+    /// Retains only the characters specified by the `predicate`, which also takes the byte
+    /// position of each character.
     ///
-    /// This method clones the [`LeanString`] if it is not unique and its capacity will be changed.
+    /// The position is the byte offset of the character in the original string, before any
+    /// character is removed. This is useful for filters which depend on the position.
     ///
     /// # Panics
     ///
-    /// Panics if cloning the [`LeanString`] fails due to the system being out-of-memory. If you
-    /// want to handle such a problem manually, use [`LeanString::try_shrink_to()`].
+    /// Panics if the system is out-of-memory when cloning the [`LeanString`]. If you want to
+    /// handle such a problem manually, use [`LeanString::try_retain_indexed()`].
     ///
     /// # Examples
     ///
     /// ```
     /// # use lean_string::LeanString;
-    /// let mut s = LeanString::with_capacity(100);
-    /// assert_eq!(s.capacity(), 100);
-    ///
-    /// // if the capacity was already bigger than the argument and unique, the call is no-op.
-    /// s.shrink_to(100);
-    /// assert_eq!(s.capacity(), 100);
-    ///
-    /// s.shrink_to(50);
-    /// assert_eq!(s.capacity(), 50);
+    /// let mut s = LeanString::from("añb€c");
     ///
-    /// // if the string can be inlined, it is
-    /// s.shrink_to(10);
-    /// assert_eq!(s.capacity(), 2 * size_of::<usize>());
+    /// // keep the bytes 1..6, regardless of what is removed before them
+    /// s.retain_indexed(|idx, _| (1..6).contains(&idx));
+    /// assert_eq!(s, "ñb€");
     /// ```
     #[inline]
-    pub fn shrink_to(&mut self, min_capacity: usize) {
-        self.try_shrink_to(min_capacity).unwrap_with_msg()
+    pub fn retain_indexed(&mut self, predicate: impl FnMut(usize, char) -> bool) {
+        self.try_retain_indexed(predicate).unwrap_with_msg()
     }
 
-    /// Fallible version of [`LeanString::shrink_to()`].
+    /// Fallible version of [`LeanString::retain_indexed()`].
     ///
-    /// This method won't panic if the system is out-of-memory, or the `capacity` is too large, but
-    /// return an [`ReserveError`]. Otherwise it behaves the same as [`LeanString::shrink_to()`].
+    /// This method won't panic if the system is out-of-memory, but return an [`ReserveError`].
+    /// Otherwise it behaves the same as [`LeanString::retain_indexed()`].
     #[inline]
-    pub fn try_shrink_to(&mut self, min_capacity: usize) -> Result<(), ReserveError> {
-        self.0.shrink_to(min_capacity)
+    pub fn try_retain_indexed(
+        &mut self,
+        predicate: impl FnMut(usize, char) -> bool,
+    ) -> Result<(), ReserveError> {
+        self.0.retain(predicate).map(|_| ())
     }
 
-    /// Appends the given [`char`] to the end of the [`LeanString`].
+    /// Keeps the characters from the start while `predicate` returns `true`, and removes the
+    /// rest from the first character for which it returns `false`.
+    ///
+    /// Unlike [`LeanString::retain()`], `predicate` is not called after the first `false`, and
+    /// the [`LeanString`] is just truncated, so nothing is moved.
     ///
     /// # Panics
     ///
-    /// Panics if the system is out-of-memory. If you want to handle such a problem manually, use
-    /// [`LeanString::try_push()`].
+    /// Panics if the system is out-of-memory when cloning the [`LeanString`]. If you want to
+    /// handle such a problem manually, use [`LeanString::try_retain_while()`].
     ///
     /// # Examples
     ///
     /// ```
     /// # use lean_string::LeanString;
-    /// let mut s = LeanString::new();
-    /// s.push('f');
-    /// s.push('o');
-    /// s.push('o');
-    /// assert_eq!("foo", s);
+    /// let mut s = LeanString::from("2024-01-31");
+    /// s.retain_while(|ch| ch.is_ascii_digit());
+    /// assert_eq!(s, "2024");
     /// ```
     #[inline]
-    pub fn push(&mut self, ch: char) {
-        self.try_push(ch).unwrap_with_msg()
+    pub fn retain_while(&mut self, predicate: impl FnMut(char) -> bool) {
+        self.try_retain_while(predicate).unwrap_with_msg()
     }
 
-    /// Fallible version of [`LeanString::push()`].
+    /// Fallible version of [`LeanString::retain_while()`].
     ///
-    /// This method won't panic if the system is out-of-memory, or the `capacity` is too large, but
-    /// return an [`ReserveError`]. Otherwise it behaves the same as [`LeanString::push()`].
+    /// This method won't panic if the system is out-of-memory, but return an [`ReserveError`].
+    /// Otherwise it behaves the same as [`LeanString::retain_while()`].
     #[inline]
-    pub fn try_push(&mut self, ch: char) -> Result<(), ReserveError> {
-        self.0.push_str(ch.encode_utf8(&mut [0; 4]))
+    pub fn try_retain_while(
+        &mut self,
+        mut predicate: impl FnMut(char) -> bool,
+    ) -> Result<(), ReserveError> {
+        match self.as_str().char_indices().find(|&(_, ch)| !predicate(ch)) {
+            Some((new_len, _)) => self.0.truncate(new_len),
+            None => Ok(()),
+        }
     }
 
-    /// Removes the last character from the [`LeanString`] and returns it.
-    /// If the [`LeanString`] is empty, `None` is returned.
+    /// Removes consecutive repeated characters in the [`LeanString`], keeping only one of each run.
+    ///
+    /// This works in place like [`Vec::dedup()`](alloc::vec::Vec::dedup), e.g. `"aaabbb"` becomes
+    /// `"ab"`.
     ///
     /// # Panics
     ///
-    /// Panics if cloning the [`LeanString`] fails due to the system being out-of-memory. If you
-    /// want to handle such a problem manually, use [`LeanString::try_pop()`].
+    /// Panics if the system is out-of-memory when cloning the [`LeanString`]. If you want to
+    /// handle such a problem manually, use [`LeanString::try_dedup_chars()`].
     ///
     /// # Examples
     ///
     /// ```
     /// # use lean_string::LeanString;
-    /// let mut s = LeanString::from("abč");
-    ///
-    /// assert_eq!(s.pop(), Some('č'));
-    /// assert_eq!(s.pop(), Some('b'));
-    /// assert_eq!(s.pop(), Some('a'));
-    ///
-    /// assert_eq!(s.pop(), None);
+    /// let mut s = LeanString::from("aaabbbcaa");
+    /// s.dedup_chars();
+    /// assert_eq!(s, "abca");
     /// ```
     #[inline]
-    pub fn pop(&mut self) -> Option<char> {
-        self.try_pop().unwrap_with_msg()
+    pub fn dedup_chars(&mut self) {
+        self.try_dedup_chars().unwrap_with_msg()
     }
 
-    /// Fallible version of [`LeanString::pop()`].
+    /// Fallible version of [`LeanString::dedup_chars()`].
     ///
-    /// This method won't panic if the system is out-of-memory, or the `capacity` is too large, but
-    /// return an [`ReserveError`]. Otherwise it behaves the same as [`LeanString::pop()`].
+    /// This method won't panic if the system is out-of-memory, but return an [`ReserveError`].
     #[inline]
-    pub fn try_pop(&mut self) -> Result<Option<char>, ReserveError> {
-        self.0.pop()
+    pub fn try_dedup_chars(&mut self) -> Result<(), ReserveError> {
+        let mut prev = None;
+        self.0
+            .retain(|_, ch| {
+                let keep = prev != Some(ch);
+                prev = Some(ch);
+                keep
+            })
+            .map(|_| ())
     }
 
-    /// Appends a given string slice onto the end of this [`LeanString`].
+    /// Retains only the ASCII alphanumeric characters (`[0-9A-Za-z]`) in the [`LeanString`].
+    ///
+    /// This is the same as `self.retain(|ch| ch.is_ascii_alphanumeric())`.
     ///
     /// # Panics
     ///
-    /// Panics if cloning the [`LeanString`] fails due to the system being out-of-memory. If you
-    /// want to handle such a problem manually, use [`LeanString::try_push_str()`].
+    /// Panics if the system is out-of-memory when cloning the [`LeanString`]. If you want to
+    /// handle such a problem manually, use [`LeanString::try_retain_ascii_alphanumeric()`].
     ///
     /// # Examples
     ///
     /// ```
     /// # use lean_string::LeanString;
-    /// let mut s = LeanString::from("foo");
-    ///
-    /// s.push_str("bar");
-    ///
-    /// assert_eq!("foobar", s);
+    /// let mut s = LeanString::from("user-name_42 ü!");
+    /// s.retain_ascii_alphanumeric();
+    /// assert_eq!(s, "username42");
     /// ```
     #[inline]
-    pub fn push_str(&mut self, string: &str) {
-        self.try_push_str(string).unwrap_with_msg()
+    pub fn retain_ascii_alphanumeric(&mut self) {
+        self.try_retain_ascii_alphanumeric().unwrap_with_msg()
     }
 
-    /// Fallible version of [`LeanString::push_str()`].
+    /// Fallible version of [`LeanString::retain_ascii_alphanumeric()`].
     ///
-    /// This method won't panic if the system is out-of-memory, or the `capacity` is too large, but
-    /// return an [`ReserveError`]. Otherwise it behaves the same as [`LeanString::push_str()`].
+    /// This method won't panic if the system is out-of-memory, but return an [`ReserveError`].
+    /// Otherwise it behaves the same as [`LeanString::retain_ascii_alphanumeric()`].
     #[inline]
-    pub fn try_push_str(&mut self, string: &str) -> Result<(), ReserveError> {
-        self.0.push_str(string)
+    pub fn try_retain_ascii_alphanumeric(&mut self) -> Result<(), ReserveError> {
+        self.try_retain(|ch| ch.is_ascii_alphanumeric())
     }
 
-    /// Removes a [`char`] from the [`LeanString`] at a byte position and returns it.
-    ///
-    /// # Panics
+    /// Retains only the characters contained in `set`.
     ///
-    /// Panics if the following conditions:
+    /// This is the same as `self.retain(|ch| set.contains(&ch))`, and is useful for stripping a
+    /// string to a whitelist.
     ///
-    /// 1. `idx` is larger than or equal tothe [`LeanString`]'s length, or if it does not lie on a [`char`]
-    /// 2. The system is out-of-memory when cloning the [`LeanString`].
+    /// # Panics
     ///
-    /// For 2, if you want to handle such a problem manually, use [`LeanString::try_remove()`].
+    /// Panics if the system is out-of-memory when cloning the [`LeanString`]. If you want to
+    /// handle such a problem manually, use [`LeanString::try_retain_matching()`].
     ///
     /// # Examples
     ///
     /// ```
     /// # use lean_string::LeanString;
-    /// let mut s = LeanString::from("Hello 世界");
+    /// let mut s = LeanString::from("+1 (555) 010-9999");
+    /// s.retain_matching(&['0', '1', '5', '9', '+']);
+    /// assert_eq!(s, "+15550109999");
+    /// ```
+    #[inline]
+    pub fn retain_matching(&mut self, set: &[char]) {
+        self.try_retain_matching(set).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::retain_matching()`].
     ///
-    /// assert_eq!(s.remove(6), '世');
-    /// assert_eq!(s.remove(1), 'e');
+    /// This method won't panic if the system is out-of-memory, but return an [`ReserveError`].
+    /// Otherwise it behaves the same as [`LeanString::retain_matching()`].
+    #[inline]
+    pub fn try_retain_matching(&mut self, set: &[char]) -> Result<(), ReserveError> {
+        self.try_retain(|ch| set.contains(&ch))
+    }
+
+    /// Converts the [`LeanString`] to its ASCII lower case equivalent in-place.
     ///
-    /// assert_eq!(s, "Hllo 界");
-    /// ```
-    /// ## Past total length:
+    /// ASCII letters `'A'` to `'Z'` are mapped to `'a'` to `'z'`, but non-ASCII letters are
+    /// unchanged. Unlike [`str::make_ascii_lowercase()`], this converts several bytes at a time, and
+    /// does not clone the buffer if there is no uppercase letter.
     ///
-    /// ```should_panic
-    /// # use lean_string::LeanString;
-    /// let mut c = LeanString::from("hello there!");
-    /// c.remove(12);
-    /// ```
+    /// # Panics
     ///
-    /// ## Not on char boundary:
+    /// Panics if the system is out-of-memory when cloning the [`LeanString`]. If you want to
+    /// handle such a problem manually, use [`LeanString::try_make_ascii_lowercase()`].
     ///
-    /// ```should_panic
+    /// # Examples
+    ///
+    /// ```
     /// # use lean_string::LeanString;
-    /// let mut c = LeanString::from("🦄");
-    /// c.remove(1);
+    /// let mut s = LeanString::from("GRüßE, JüRGEN ❤");
+    /// s.make_ascii_lowercase();
+    /// assert_eq!(s, "grüße, jürgen ❤");
     /// ```
     #[inline]
-    pub fn remove(&mut self, idx: usize) -> char {
-        self.try_remove(idx).unwrap_with_msg()
+    pub fn make_ascii_lowercase(&mut self) {
+        self.try_make_ascii_lowercase().unwrap_with_msg()
     }
 
-    /// Fallible version of [`LeanString::remove()`].
+    /// Fallible version of [`LeanString::make_ascii_lowercase()`].
     ///
     /// This method won't panic if the system is out-of-memory, but return an [`ReserveError`].
-    /// Otherwise it behaves the same as [`LeanString::remove()`].
+    /// Otherwise it behaves the same as [`LeanString::make_ascii_lowercase()`].
     #[inline]
-    pub fn try_remove(&mut self, idx: usize) -> Result<char, ReserveError> {
-        self.0.remove(idx)
+    pub fn try_make_ascii_lowercase(&mut self) -> Result<(), ReserveError> {
+        self.0.make_ascii_lowercase()
     }
 
-    /// Retains only the characters specified by the `predicate`.
+    /// Swaps the case of each ASCII letter in the [`LeanString`] in-place.
     ///
-    /// If the `predicate` returns `true`, the character is kept, otherwise it is removed.
+    /// ASCII letters `'A'` to `'Z'` are mapped to `'a'` to `'z'` and vice versa, but other
+    /// characters, including non-ASCII letters, are unchanged. Like
+    /// [`LeanString::make_ascii_lowercase()`], this converts several bytes at a time, and does
+    /// not clone the buffer if there is no ASCII letter.
     ///
     /// # Panics
     ///
     /// Panics if the system is out-of-memory when cloning the [`LeanString`]. If you want to
-    /// handle such a problem manually, use [`LeanString::try_retain()`].
+    /// handle such a problem manually, use [`LeanString::try_swap_ascii_case()`].
     ///
     /// # Examples
     ///
     /// ```
     /// # use lean_string::LeanString;
-    /// let mut s = LeanString::from("äb𝄞d€");
+    /// let mut s = LeanString::from("Hello, Wörld 123!");
+    /// s.swap_ascii_case();
+    /// assert_eq!(s, "hELLO, wöRLD 123!");
+    /// ```
+    #[inline]
+    pub fn swap_ascii_case(&mut self) {
+        self.try_swap_ascii_case().unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::swap_ascii_case()`].
     ///
-    /// let keep = [false, true, true, false, true];
-    /// let mut iter = keep.iter();
-    /// s.retain(|_| *iter.next().unwrap());
+    /// This method won't panic if the system is out-of-memory, but return an [`ReserveError`].
+    /// Otherwise it behaves the same as [`LeanString::swap_ascii_case()`].
+    #[inline]
+    pub fn try_swap_ascii_case(&mut self) -> Result<(), ReserveError> {
+        self.0.swap_ascii_case()
+    }
+
+    /// Reverses the order of the [`char`]s in the [`LeanString`] in-place.
+    ///
+    /// This reverses Unicode scalar values, not bytes, so the result is always valid UTF-8. Note
+    /// that grapheme clusters made of several [`char`]s, such as a letter followed by a combining
+    /// mark, are split up.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory when cloning the [`LeanString`]. If you want to
+    /// handle such a problem manually, use [`LeanString::try_reverse()`].
+    ///
+    /// # Examples
     ///
-    /// assert_eq!(s, "b𝄞€");
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::from("añ🦀z");
+    /// s.reverse();
+    /// assert_eq!(s, "z🦀ña");
     /// ```
     #[inline]
-    pub fn retain(&mut self, predicate: impl FnMut(char) -> bool) {
-        self.try_retain(predicate).unwrap_with_msg()
+    pub fn reverse(&mut self) {
+        self.try_reverse().unwrap_with_msg()
     }
 
-    /// Fallible version of [`LeanString::retain()`].
+    /// Fallible version of [`LeanString::reverse()`].
     ///
     /// This method won't panic if the system is out-of-memory, but return an [`ReserveError`].
+    /// Otherwise it behaves the same as [`LeanString::reverse()`].
     #[inline]
-    pub fn try_retain(&mut self, predicate: impl FnMut(char) -> bool) -> Result<(), ReserveError> {
-        self.0.retain(predicate)
+    pub fn try_reverse(&mut self) -> Result<(), ReserveError> {
+        self.0.reverse()
     }
 
     /// Inserts a character into the [`LeanString`] at a byte position.
@@ -683,7 +3073,7 @@ impl LeanString {
     ///    boundary.
     /// 2. The system is out-of-memory when cloning the [`LeanString`].
     /// 3. The length of after inserting is greater than `2^56 - 1` on 64-bit architecture, or
-    ///    `2^32 - 1` on 32-bit architecture.
+    ///    `2^24 - 2` on 32-bit architecture.
     ///
     /// For 2 and 3, if you want to handle such a problem manually, use [`LeanString::try_insert()`].
     ///
@@ -719,6 +3109,61 @@ impl LeanString {
         self.0.insert_str(idx, ch.encode_utf8(&mut [0; 4]))
     }
 
+    /// Inserts a character `n` times into the [`LeanString`] at a byte position.
+    ///
+    /// This is more efficient than calling [`LeanString::insert()`] `n` times, because the tail of
+    /// the string is shifted only once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the following conditions:
+    ///
+    /// 1. `idx` is larger than the [`LeanString`]'s length, or if it does not lie on a [`char`]
+    ///    boundary.
+    /// 2. The system is out-of-memory when cloning the [`LeanString`].
+    /// 3. The length of after inserting is greater than `2^56 - 1` on 64-bit architecture, or
+    ///    `2^24 - 2` on 32-bit architecture.
+    ///
+    /// For 2 and 3, if you want to handle such a problem manually, use
+    /// [`LeanString::try_insert_char_repeated()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::from("[]");
+    ///
+    /// s.insert_char_repeated(1, '-', 3);
+    /// assert_eq!(s, "[---]");
+    ///
+    /// s.insert_char_repeated(5, 'あ', 2);
+    /// assert_eq!(s, "[---]ああ");
+    /// ```
+    #[inline]
+    pub fn insert_char_repeated(&mut self, idx: usize, ch: char, n: usize) {
+        self.try_insert_char_repeated(idx, ch, n).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::insert_char_repeated()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, or the `capacity` becomes too large
+    /// by inserting characters, but return an [`ReserveError`]. Otherwise it behaves the same as
+    /// [`LeanString::insert_char_repeated()`].
+    ///
+    /// # Panics
+    ///
+    /// This method still panics if the `idx` is larger than the [`LeanString`]'s length, or if it
+    /// does not lie on a [`char`] boundary.
+    #[inline]
+    pub fn try_insert_char_repeated(
+        &mut self,
+        idx: usize,
+        ch: char,
+        n: usize,
+    ) -> Result<(), ReserveError> {
+        self.0.insert_char_repeated(idx, ch, n)
+    }
+
     /// Inserts a string slice into the [`LeanString`] at a byte position.
     ///
     /// # Panics
@@ -728,7 +3173,7 @@ impl LeanString {
     /// 1. `idx` is larger than the [`LeanString`]'s length, or if it does not lie on a [`char`] boundary.
     /// 2. The system is out-of-memory when cloning the [`LeanString`].
     /// 3. The length of after inserting is greater than `2^56 - 1` on 64-bit architecture, or
-    ///    `2^32 - 1` on 32-bit architecture.
+    ///    `2^24 - 2` on 32-bit architecture.
     ///
     /// For 2 and 3, if you want to handle such a problem manually, use [`LeanString::try_insert_str()`].
     ///
@@ -759,6 +3204,65 @@ impl LeanString {
         self.0.insert_str(idx, string)
     }
 
+    /// Inserts a string slice into the [`LeanString`] at a byte position, returning an error
+    /// instead of panicking.
+    ///
+    /// On error, the [`LeanString`] is unchanged.
+    ///
+    /// # Errors
+    ///
+    /// - [`InsertError::OutOfBounds`] if `idx` is larger than the [`LeanString`]'s length.
+    /// - [`InsertError::NotCharBoundary`] if `idx` does not lie on a [`char`] boundary.
+    /// - [`InsertError::Reserve`] if the system is out-of-memory, or the length of after
+    ///   inserting is too large.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::{InsertError, LeanString};
+    /// let mut s = LeanString::from("äc");
+    ///
+    /// assert_eq!(s.checked_insert_str(2, "b"), Ok(()));
+    /// assert_eq!(s, "äbc");
+    ///
+    /// assert_eq!(s.checked_insert_str(1, "x"), Err(InsertError::NotCharBoundary));
+    /// assert_eq!(s.checked_insert_str(5, "x"), Err(InsertError::OutOfBounds));
+    /// assert_eq!(s, "äbc");
+    /// ```
+    #[inline]
+    pub fn checked_insert_str(&mut self, idx: usize, string: &str) -> Result<(), InsertError> {
+        if idx > self.len() {
+            return Err(InsertError::OutOfBounds);
+        }
+        if !self.is_char_boundary(idx) {
+            return Err(InsertError::NotCharBoundary);
+        }
+        self.0.insert_str(idx, string)?;
+        Ok(())
+    }
+
+    /// Returns a guard which truncates the [`LeanString`] back to its current length when
+    /// dropped.
+    ///
+    /// The guard dereferences to the [`LeanString`]. See [`ScopedLen`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::from("key");
+    /// {
+    ///     let mut scoped = s.with_saved_len();
+    ///     scoped.push_str("-suffix");
+    ///     assert_eq!(*scoped, "key-suffix");
+    /// }
+    /// assert_eq!(s, "key");
+    /// ```
+    #[inline]
+    pub fn with_saved_len(&mut self) -> ScopedLen<'_> {
+        ScopedLen::new(self)
+    }
+
     /// Reduces the length of the [`LeanString`] to zero.
     ///
     /// If the [`LeanString`] is unique, this method will not change the capacity.
@@ -790,7 +3294,7 @@ impl LeanString {
     /// s.clear();
     ///
     /// assert_eq!(s, "");
-    /// assert_eq!(s.capacity(), 2 * size_of::<usize>());
+    /// assert_eq!(s.capacity(), size_of::<LeanString>());
     /// ```
     #[inline]
     pub fn clear(&mut self) {
@@ -804,6 +3308,157 @@ impl LeanString {
         }
     }
 
+    /// Replaces the contents of the [`LeanString`] with `string`.
+    ///
+    /// If the buffer is unique (not shared nor static) and has enough capacity, `string` is
+    /// copied into it without reallocation, like [`LeanString::clear()`] followed by
+    /// [`LeanString::push_str()`]. Otherwise, a new buffer is created for `string`. This is
+    /// useful for a reused scratch buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is out-of-memory, or the length is too large. If you want to handle
+    /// such a problem manually, use [`LeanString::try_assign()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::with_capacity(100);
+    ///
+    /// s.assign("a heap allocated string, longer than inline");
+    /// assert_eq!(s, "a heap allocated string, longer than inline");
+    /// assert_eq!(s.capacity(), 100);
+    ///
+    /// s.assign("short");
+    /// assert_eq!(s, "short");
+    /// assert_eq!(s.capacity(), 100);
+    /// ```
+    #[inline]
+    pub fn assign(&mut self, string: &str) {
+        self.try_assign(string).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`LeanString::assign()`].
+    ///
+    /// This method won't panic if the system is out-of-memory, or the length is too large, but
+    /// return an [`ReserveError`]. Otherwise it behaves the same as [`LeanString::assign()`]. On
+    /// error, `self` is unchanged.
+    pub fn try_assign(&mut self, string: &str) -> Result<(), ReserveError> {
+        if string.len() <= self.capacity() && self.try_as_unique_mut().is_some() {
+            self.clear();
+            // The capacity is enough, so this never reallocates.
+            self.try_push_str(string)
+        } else {
+            let next = Repr::from_str(string)?;
+            self.0.replace_inner(next);
+            Ok(())
+        }
+    }
+
+    /// Swaps the contents of two [`LeanString`]s in `O(1)`.
+    ///
+    /// This never allocates, and never touches the reference counts of heap buffers because the
+    /// ownership of the buffers is just moved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut a = LeanString::from("short");
+    /// let mut b = LeanString::from("This is a long string that is heap allocated");
+    ///
+    /// a.swap(&mut b);
+    ///
+    /// assert_eq!(a, "This is a long string that is heap allocated");
+    /// assert_eq!(b, "short");
+    /// ```
+    #[inline]
+    pub fn swap(&mut self, other: &mut LeanString) {
+        core::mem::swap(&mut self.0, &mut other.0);
+    }
+
+    /// Clones the [`LeanString`] without copying a heap buffer.
+    ///
+    /// This is exactly what [`Clone::clone()`] does: for a heap buffer, only the reference count
+    /// is incremented and the buffer is shared, so this never allocates. The buffer is copied
+    /// lazily when either of them is modified. Use this to make the intent explicit where the
+    /// distinction from [`LeanString::deep_clone()`] matters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from("This is a long string that is heap allocated");
+    /// let shallow = s.shallow_clone();
+    /// assert_eq!(shallow.as_ptr(), s.as_ptr());
+    /// ```
+    #[inline]
+    pub fn shallow_clone(&self) -> LeanString {
+        self.clone()
+    }
+
+    /// Clones the [`LeanString`] into a unique buffer.
+    ///
+    /// Unlike [`LeanString::shallow_clone()`], a heap (or static) buffer is copied, so the clone
+    /// doesn't share anything with `self` and can be modified without copying again.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ReserveError`] if the system is out-of-memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from("This is a long string that is heap allocated");
+    /// let deep = s.deep_clone().unwrap();
+    /// assert_eq!(deep, s);
+    /// assert_ne!(deep.as_ptr(), s.as_ptr());
+    /// ```
+    #[inline]
+    pub fn deep_clone(&self) -> Result<LeanString, ReserveError> {
+        Repr::from_str(self.as_str()).map(LeanString)
+    }
+
+    /// Decomposes the [`LeanString`] into its [`RawParts`], without dropping it.
+    ///
+    /// This is useful for passing the ownership of a [`LeanString`] through FFI without copying.
+    /// The returned parts must be converted back by [`LeanString::from_raw()`] exactly once,
+    /// otherwise the heap buffer is leaked. The parts are only valid for the same build of this
+    /// crate, because their layout depends on the target and the enabled features.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from("This is a heap allocated string");
+    /// let parts = s.into_raw();
+    ///
+    /// // SAFETY: `parts` comes from `into_raw` and is used only once.
+    /// let s = unsafe { LeanString::from_raw(parts) };
+    /// assert_eq!(s, "This is a heap allocated string");
+    /// ```
+    #[inline]
+    pub fn into_raw(self) -> RawParts {
+        let this = core::mem::ManuallyDrop::new(self);
+        // SAFETY: `this` is never used (and dropped) again, so the ownership moves to the parts.
+        RawParts(unsafe { core::ptr::read(&this.0) })
+    }
+
+    /// Reconstructs a [`LeanString`] from the [`RawParts`] returned by [`LeanString::into_raw()`].
+    ///
+    /// # Safety
+    ///
+    /// - `parts` must come from [`LeanString::into_raw()`] of the same build of this crate,
+    ///   without modification.
+    /// - `parts` must not be used again after this call (e.g. by copying its bytes before),
+    ///   because the reference count of the heap buffer is not incremented.
+    #[inline]
+    pub unsafe fn from_raw(parts: RawParts) -> LeanString {
+        LeanString(parts.0)
+    }
+
     /// Returns whether the [`LeanString`] is heap-allocated.
     ///
     /// # Examples
@@ -824,9 +3479,59 @@ impl LeanString {
     /// assert!(s.is_heap_allocated());
     /// ```
     #[inline]
-    pub fn is_heap_allocated(&self) -> bool {
+    pub const fn is_heap_allocated(&self) -> bool {
         self.0.is_heap_buffer()
     }
+
+    /// Returns a value whose [`fmt::Debug`] output shows the internal representation of the
+    /// [`LeanString`].
+    ///
+    /// Besides the string, the representation (`Inline`, `Heap` or `Static`), length, capacity,
+    /// and reference count (only for `Heap`) are shown, which helps to diagnose unexpected
+    /// allocations. The [`fmt::Debug`] implementation of [`LeanString`] itself shows only the
+    /// string, the same as `str`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let s = LeanString::from("abc");
+    /// assert_eq!(format!("{s:?}"), r#""abc""#);
+    ///
+    /// let repr = format!("{:?}", s.debug_repr());
+    /// assert!(repr.contains("kind: Inline"));
+    /// assert!(repr.contains("len: 3"));
+    /// ```
+    #[inline]
+    pub fn debug_repr(&self) -> impl fmt::Debug + '_ {
+        DebugRepr(self)
+    }
+
+    /// Checks the internal invariants of the [`LeanString`], and panics if any of them is broken.
+    ///
+    /// The following are checked:
+    ///
+    /// - `len() <= capacity()`.
+    /// - The contents are valid UTF-8.
+    /// - The last byte has the correct tag for the representation (inline, heap or static).
+    /// - The reference count of a heap buffer is at least 1.
+    ///
+    /// This is intended for property tests and fuzzers, to be called after each operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lean_string::LeanString;
+    /// let mut s = LeanString::from("abc");
+    /// s.push_str("this makes it heap allocated");
+    /// s.assert_invariants();
+    /// ```
+    #[cfg(any(test, feature = "internal-debug"))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "internal-debug")))]
+    #[track_caller]
+    pub fn assert_invariants(&self) {
+        self.0.assert_invariants();
+    }
 }
 
 impl Clone for LeanString {
@@ -873,6 +3578,32 @@ impl fmt::Debug for LeanString {
     }
 }
 
+/// The [`fmt::Debug`] output of [`LeanString::debug_repr()`].
+struct DebugRepr<'a>(&'a LeanString);
+
+impl fmt::Debug for DebugRepr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let DebugRepr(s) = self;
+        let kind = if s.0.is_heap_buffer() {
+            "Heap"
+        } else if s.0.as_static_str().is_some() {
+            "Static"
+        } else {
+            "Inline"
+        };
+        let mut debug = f.debug_struct("LeanString");
+        debug
+            .field("str", &s.as_str())
+            .field("kind", &format_args!("{kind}"))
+            .field("len", &s.len())
+            .field("capacity", &s.capacity());
+        if let Some(count) = s.0.reference_count() {
+            debug.field("ref_count", &count);
+        }
+        debug.finish()
+    }
+}
+
 impl fmt::Display for LeanString {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Display::fmt(self.as_str(), f)
@@ -973,6 +3704,85 @@ impl PartialEq<LeanString> for Cow<'_, str> {
     }
 }
 
+impl PartialEq<Box<str>> for LeanString {
+    #[inline]
+    fn eq(&self, other: &Box<str>) -> bool {
+        self.as_str().eq(other.as_ref())
+    }
+}
+
+impl PartialEq<LeanString> for Box<str> {
+    #[inline]
+    fn eq(&self, other: &LeanString) -> bool {
+        self.as_ref().eq(other.as_str())
+    }
+}
+
+impl PartialEq<Rc<str>> for LeanString {
+    #[inline]
+    fn eq(&self, other: &Rc<str>) -> bool {
+        self.as_str().eq(other.as_ref())
+    }
+}
+
+impl PartialEq<LeanString> for Rc<str> {
+    #[inline]
+    fn eq(&self, other: &LeanString) -> bool {
+        self.as_ref().eq(other.as_str())
+    }
+}
+
+impl PartialEq<Arc<str>> for LeanString {
+    #[inline]
+    fn eq(&self, other: &Arc<str>) -> bool {
+        self.as_str().eq(other.as_ref())
+    }
+}
+
+impl PartialEq<LeanString> for Arc<str> {
+    #[inline]
+    fn eq(&self, other: &LeanString) -> bool {
+        self.as_ref().eq(other.as_str())
+    }
+}
+
+/// Compares with the string form of the [`OsStr`]. An [`OsStr`] which is not valid UTF-8 is
+/// never equal to a [`LeanString`].
+#[cfg(feature = "std")]
+impl PartialEq<OsStr> for LeanString {
+    #[inline]
+    fn eq(&self, other: &OsStr) -> bool {
+        other.to_str() == Some(self.as_str())
+    }
+}
+
+#[cfg(feature = "std")]
+impl PartialEq<LeanString> for OsStr {
+    #[inline]
+    fn eq(&self, other: &LeanString) -> bool {
+        other.eq(self)
+    }
+}
+
+/// Compares with the string form of the [`Path`], not component-wise like `Path == Path`, so
+/// `"a//b"` is not equal to `Path::new("a/b")`. A [`Path`] which is not valid UTF-8 is never
+/// equal to a [`LeanString`].
+#[cfg(feature = "std")]
+impl PartialEq<Path> for LeanString {
+    #[inline]
+    fn eq(&self, other: &Path) -> bool {
+        other.to_str() == Some(self.as_str())
+    }
+}
+
+#[cfg(feature = "std")]
+impl PartialEq<LeanString> for Path {
+    #[inline]
+    fn eq(&self, other: &LeanString) -> bool {
+        other.eq(self)
+    }
+}
+
 impl Ord for LeanString {
     #[inline]
     fn cmp(&self, other: &Self) -> cmp::Ordering {
@@ -1002,6 +3812,27 @@ impl From<char> for LeanString {
     }
 }
 
+impl From<&[char]> for LeanString {
+    #[track_caller]
+    fn from(value: &[char]) -> Self {
+        // Reserve the exact length (at most `4 * value.len()`), so short ones stay inline.
+        let len = value.iter().map(|ch| ch.len_utf8()).sum();
+        let mut ret = LeanString::with_capacity(len);
+        for &ch in value {
+            ret.push(ch);
+        }
+        ret
+    }
+}
+
+impl<const N: usize> From<[char; N]> for LeanString {
+    #[inline]
+    #[track_caller]
+    fn from(value: [char; N]) -> Self {
+        LeanString::from(value.as_slice())
+    }
+}
+
 impl From<&str> for LeanString {
     #[inline]
     #[track_caller]
@@ -1010,6 +3841,17 @@ impl From<&str> for LeanString {
     }
 }
 
+impl From<&[&str]> for LeanString {
+    #[track_caller]
+    fn from(value: &[&str]) -> Self {
+        let mut ret = LeanString::with_capacity_for(value).unwrap_with_msg();
+        for s in value {
+            ret.push_str(s);
+        }
+        ret
+    }
+}
+
 impl From<String> for LeanString {
     #[inline]
     #[track_caller]
@@ -1018,6 +3860,14 @@ impl From<String> for LeanString {
     }
 }
 
+impl From<&mut str> for LeanString {
+    #[inline]
+    #[track_caller]
+    fn from(value: &mut str) -> Self {
+        LeanString(Repr::from_str(value).unwrap_with_msg())
+    }
+}
+
 impl From<&String> for LeanString {
     #[inline]
     #[track_caller]
@@ -1043,6 +3893,14 @@ impl From<Box<str>> for LeanString {
     }
 }
 
+impl From<fmt::Arguments<'_>> for LeanString {
+    #[inline]
+    #[track_caller]
+    fn from(value: fmt::Arguments<'_>) -> Self {
+        LeanString::from_fmt(value)
+    }
+}
+
 impl From<&LeanString> for LeanString {
     #[inline]
     fn from(value: &LeanString) -> Self {
@@ -1077,6 +3935,9 @@ impl FromIterator<char> for LeanString {
     fn from_iter<T: IntoIterator<Item = char>>(iter: T) -> Self {
         let iter = iter.into_iter();
 
+        // The lower bound is in chars, but a char is at least 1 byte, so reserving it as bytes
+        // never over-allocates. Non-ASCII chars are left to the amortized growth of `push_str`,
+        // same as `String`.
         let (lower_bound, _) = iter.size_hint();
         let mut repr = match Repr::with_capacity(lower_bound) {
             Ok(buf) => buf,
@@ -1140,6 +4001,7 @@ impl Extend<char> for LeanString {
     fn extend<T: IntoIterator<Item = char>>(&mut self, iter: T) {
         let iter = iter.into_iter();
 
+        // See `FromIterator<char>` for why the lower bound is reserved as bytes.
         let (lower_bound, _) = iter.size_hint();
         // Ignore the error and hope that the lower_bound is incorrect.
         let _ = self.try_reserve(lower_bound);
@@ -1148,6 +4010,19 @@ impl Extend<char> for LeanString {
             self.push(ch);
         }
     }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn extend_one(&mut self, ch: char) {
+        self.push(ch);
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn extend_reserve(&mut self, additional: usize) {
+        // Same as `extend`, ignore the error and let pushing report it.
+        let _ = self.try_reserve(additional);
+    }
 }
 
 impl<'a> Extend<&'a char> for LeanString {
@@ -1160,30 +4035,106 @@ impl<'a> Extend<&'a str> for LeanString {
     fn extend<T: IntoIterator<Item = &'a str>>(&mut self, iter: T) {
         iter.into_iter().for_each(|s| self.push_str(s));
     }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn extend_one(&mut self, s: &'a str) {
+        self.push_str(s);
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn extend_reserve(&mut self, additional: usize) {
+        // `additional` is the number of strings, so this is a lower bound of at least one byte
+        // per string. Same as `extend`, ignore the error and let pushing report it.
+        let _ = self.try_reserve(additional);
+    }
 }
 
 impl Extend<Box<str>> for LeanString {
     fn extend<T: IntoIterator<Item = Box<str>>>(&mut self, iter: T) {
-        iter.into_iter().for_each(move |s| self.push_str(&s));
+        extend_in_chunks(self, iter);
     }
 }
 
 impl<'a> Extend<Cow<'a, str>> for LeanString {
     fn extend<T: IntoIterator<Item = Cow<'a, str>>>(&mut self, iter: T) {
-        iter.into_iter().for_each(move |s| self.push_str(&s));
+        extend_in_chunks(self, iter);
     }
 }
 
 impl Extend<String> for LeanString {
     fn extend<T: IntoIterator<Item = String>>(&mut self, iter: T) {
-        iter.into_iter().for_each(move |s| self.push_str(&s));
+        extend_in_chunks(self, iter);
     }
 }
 
 impl Extend<LeanString> for LeanString {
     fn extend<T: IntoIterator<Item = LeanString>>(&mut self, iter: T) {
-        for s in iter {
-            self.push_str(&s);
+        extend_in_chunks(self, iter);
+    }
+}
+
+/// Returns an iterator over the start indices of non-overlapping matches of `needle`.
+fn match_indices<'a>(haystack: &'a str, needle: &'a str) -> impl Iterator<Item = usize> + 'a {
+    #[cfg(feature = "memchr")]
+    let mut fast = features::memchr::find_iter(haystack, needle);
+    #[cfg(not(feature = "memchr"))]
+    let mut fast: Option<core::iter::Empty<usize>> = None;
+
+    let mut slow = haystack.match_indices(needle).map(|(i, _)| i);
+    core::iter::from_fn(move || match &mut fast {
+        Some(fast) => fast.next(),
+        None => slow.next(),
+    })
+}
+
+/// Converts `range` to a [`Range`] in a string of `len` bytes, or returns [`None`] if an end
+/// overflows.
+#[inline]
+fn to_range(range: impl RangeBounds<usize>, len: usize) -> Option<Range<usize>> {
+    let start = match range.start_bound() {
+        Bound::Included(&n) => n,
+        Bound::Excluded(&n) => n.checked_add(1)?,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&n) => n.checked_add(1)?,
+        Bound::Excluded(&n) => n,
+        Bound::Unbounded => len,
+    };
+    Some(start..end)
+}
+
+/// Returns `true` if `byte` is not a UTF-8 continuation byte (`0b10xx_xxxx`).
+#[inline]
+const fn is_utf8_char_boundary(byte: u8) -> bool {
+    (byte as i8) >= -0x40
+}
+
+/// Extends `this` by taking items in chunks on the stack, and reserving the total length of each
+/// chunk at once, so that the buffer grows at most once per chunk.
+fn extend_in_chunks<S: AsRef<str>>(this: &mut LeanString, iter: impl IntoIterator<Item = S>) {
+    const CHUNK_SIZE: usize = 16;
+
+    let mut iter = iter.into_iter().fuse();
+    loop {
+        let chunk: [Option<S>; CHUNK_SIZE] = core::array::from_fn(|_| iter.next());
+
+        let additional =
+            chunk.iter().flatten().fold(0, |acc: usize, s| acc.saturating_add(s.as_ref().len()));
+        // Ignore the error here, `push_str` will report it.
+        let _ = this.try_reserve(additional);
+
+        let mut exhausted = false;
+        for s in chunk {
+            match s {
+                Some(s) => this.push_str(s.as_ref()),
+                None => exhausted = true,
+            }
+        }
+        if exhausted {
+            break;
         }
     }
 }
@@ -1202,6 +4153,11 @@ impl fmt::Write for LeanString {
         self.push_str(s);
         Ok(())
     }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.try_push(c).map_err(|_| fmt::Error)
+    }
 }
 
 impl Add<&str> for LeanString {
@@ -1214,6 +4170,24 @@ impl Add<&str> for LeanString {
     }
 }
 
+impl Add<LeanString> for LeanString {
+    type Output = Self;
+
+    /// Concatenates two [`LeanString`]s.
+    ///
+    /// If `self` is empty, `rhs` is returned as it is, so its buffer is reused without copying.
+    /// Otherwise `rhs` is appended to `self`, in place if `self` is unique and has enough
+    /// capacity.
+    #[inline]
+    fn add(mut self, rhs: LeanString) -> Self::Output {
+        if self.is_empty() {
+            return rhs;
+        }
+        self.push_str(&rhs);
+        self
+    }
+}
+
 impl AddAssign<&str> for LeanString {
     #[inline]
     fn add_assign(&mut self, rhs: &str) {
@@ -0,0 +1,24 @@
+use crate::repr::Repr;
+use core::fmt;
+
+/// The raw parts of a [`LeanString`], returned by [`LeanString::into_raw()`].
+///
+/// This is an opaque value with the same size and alignment as [`LeanString`]. It owns the
+/// buffer of the [`LeanString`], but never drops it, so it must be converted back by
+/// [`LeanString::from_raw()`] exactly once, otherwise the heap buffer is leaked.
+///
+/// The layout depends on the target and the enabled features (e.g. `wide_inline`), so the parts
+/// are only valid for the same build of this crate. Don't send them to a program built
+/// with another configuration.
+///
+/// [`LeanString`]: crate::LeanString
+/// [`LeanString::into_raw()`]: crate::LeanString::into_raw
+/// [`LeanString::from_raw()`]: crate::LeanString::from_raw
+#[repr(transparent)]
+pub struct RawParts(pub(crate) Repr);
+
+impl fmt::Debug for RawParts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawParts").finish_non_exhaustive()
+    }
+}
@@ -1,6 +1,6 @@
 use super::ReserveError;
 
-use core::{mem, ptr, slice, str};
+use core::{mem, mem::MaybeUninit, ptr, slice, str};
 
 #[cfg(not(loom))]
 use core::sync::atomic::{fence, Ordering::*};
@@ -25,11 +25,25 @@ pub use last_byte::LastByte;
 mod num_to_repr;
 use num_to_repr::NumToRepr;
 
-const MAX_INLINE_SIZE: usize = 2 * size_of::<usize>();
+/// The number of words (`usize`) in a [`Repr`].
+///
+/// With the `wide_inline` feature, [`Repr`] grows to 3 words to keep longer strings inline.
+#[cfg(not(feature = "wide_inline"))]
+const REPR_WORDS: usize = 2;
+#[cfg(feature = "wide_inline")]
+const REPR_WORDS: usize = 3;
 
+pub(crate) const MAX_INLINE_SIZE: usize = REPR_WORDS * size_of::<usize>();
+
+/// The maximum capacity of a heap allocated [`Repr`].
+pub(crate) const MAX_HEAP_CAPACITY: usize = HeapBuffer::MAX_CAPACITY;
+
+/// The size of the bytes between the first word and the last byte of [`Repr`].
+pub(crate) const MIDDLE_SIZE: usize = MAX_INLINE_SIZE - size_of::<usize>() - 1;
+
+// e.g. `Repr(*const (), [u8; 7], LastByte)` on 64-bit architecture without `wide_inline`.
 #[repr(C)]
-#[cfg(target_pointer_width = "64")]
-pub(crate) struct Repr(*const (), [u8; 7], LastByte);
+pub(crate) struct Repr(*const (), [u8; MIDDLE_SIZE], LastByte);
 
 fn _static_assert() {
     const {
@@ -107,24 +121,25 @@ impl Repr {
         }
     }
 
-    #[cfg(target_pointer_width = "64")]
     #[inline]
-    pub(crate) fn len(&self) -> usize {
+    pub(crate) const fn len(&self) -> usize {
+        const USIZE_SIZE: usize = size_of::<usize>();
+
         let mut len = {
-            // SAFETY:`Repr` is same size of [usize; 2], and aligned as usize
+            // SAFETY:`Repr` is same size of [usize; REPR_WORDS], and aligned as usize
             let mut tail_bytes = unsafe {
-                let tail = (self as *const _ as *const usize).add(1);
-                *(tail as *const [u8; 8])
+                let tail = (self as *const _ as *const usize).add(REPR_WORDS - 1);
+                *(tail as *const [u8; USIZE_SIZE])
             };
-            tail_bytes[7] = 0;
+            tail_bytes[USIZE_SIZE - 1] = 0;
             usize::from_le_bytes(tail_bytes)
         };
 
         let last_byte = self.last_byte();
 
-        let inline_len = (last_byte as usize)
-            .wrapping_sub(LastByte::MASK_1100_0000 as usize)
-            .min(MAX_INLINE_SIZE);
+        let inline_len = (last_byte as usize).wrapping_sub(LastByte::MASK_1100_0000 as usize);
+        // `Ord::min` is not const
+        let inline_len = if inline_len < MAX_INLINE_SIZE { inline_len } else { MAX_INLINE_SIZE };
 
         // This code is compiled to a single branchless instruction, such as `cmov`
         if last_byte < LastByte::HeapMarker as u8 {
@@ -135,7 +150,7 @@ impl Repr {
     }
 
     #[inline]
-    pub(crate) fn is_empty(&self) -> bool {
+    pub(crate) const fn is_empty(&self) -> bool {
         self.len() == 0
     }
 
@@ -158,6 +173,17 @@ impl Repr {
         unsafe { str::from_utf8_unchecked(self.as_bytes()) }
     }
 
+    /// Returns the string with `'static` lifetime if the buffer is StaticBuffer.
+    #[inline]
+    pub(crate) fn as_static_str(&self) -> Option<&'static str> {
+        if self.is_static_buffer() {
+            // SAFETY: We just checked that `self` is StaticBuffer
+            Some(unsafe { self.as_static_buffer() }.as_str())
+        } else {
+            None
+        }
+    }
+
     #[inline]
     pub(crate) fn as_bytes(&self) -> &[u8] {
         let len = self.len();
@@ -175,6 +201,22 @@ impl Repr {
 
     #[inline]
     pub(crate) fn reserve(&mut self, additional: usize) -> Result<(), ReserveError> {
+        self.reserve_with(additional, heap_buffer::amortized_growth)
+    }
+
+    #[inline]
+    pub(crate) fn reserve_pow2(&mut self, additional: usize) -> Result<(), ReserveError> {
+        self.reserve_with(additional, heap_buffer::pow2_growth)
+    }
+
+    /// Reserves capacity for `additional` bytes, where the capacity of a new or reallocated
+    /// HeapBuffer is computed by `growth(len, additional)`.
+    #[inline]
+    fn reserve_with(
+        &mut self,
+        additional: usize,
+        growth: fn(usize, usize) -> usize,
+    ) -> Result<(), ReserveError> {
         let len = self.len();
         let needed_capacity = len.checked_add(additional).ok_or(ReserveError)?;
 
@@ -182,33 +224,29 @@ impl Repr {
             // SAFETY: We just checked that `self` is HeapBuffer
             let heap = unsafe { self.as_heap_buffer_mut() };
 
-            // Because `fetch_sub` is already atomic, we should use `Release` ordering to avoid
-            // unexpected drop of the buffer and to ensure that the buffer is unique.
-            if heap.reference_count().fetch_sub(1, Release) == 1 {
+            // `is_unique` loads the reference count with `Acquire` ordering, so that the drops of
+            // the other references happen-before the reallocation. No one can clone `self` while
+            // we hold `&mut self`, so the buffer stays unique.
+            if heap.is_unique() {
                 // `heap` is unique, we can reallocate in place.
 
-                // We need to rollback the reference count.
-                // We should use `Acquire` ordering to prevent reordering of the reallocation and
-                // the reference count increment.
-                // This is a same meaning of `fence(Acquire); fech_add(1, Relaxed);`
-                heap.reference_count().fetch_add(1, Acquire);
-
                 if heap.capacity() >= needed_capacity {
                     // No need to reserve more capacity.
                     return Ok(());
                 }
 
-                let amortized_capacity = heap_buffer::amortized_growth(len, additional);
+                let new_capacity = growth(len, additional);
                 // SAFETY:
                 // - `heap` is unique.
-                // - `amortized_capacity` is greater than `len`.
-                unsafe { heap.realloc(amortized_capacity)? };
+                // - `new_capacity` is greater than `len`.
+                unsafe { heap.realloc(new_capacity)? };
             } else {
                 // heap is shared, we need to reallocate a new buffer.
-                // We already decremented the reference count, no need to touch it again.
+                // The reference to the current buffer is released only after the allocation
+                // succeeded, so `self` is unchanged on error.
                 let str = heap.as_str();
-                let new_heap = HeapBuffer::with_additional(str, additional)?;
-                *self = Repr::from_heap(new_heap);
+                let new_heap = HeapBuffer::with_new_capacity(str, growth(len, additional))?;
+                self.replace_inner(Repr::from_heap(new_heap));
             }
             Ok(())
         } else if self.is_static_buffer() {
@@ -219,7 +257,8 @@ impl Repr {
                 let inline = unsafe { InlineBuffer::new(self.as_str()) };
                 *self = Repr::from_inline(inline);
             } else {
-                let heap = HeapBuffer::with_additional(self.as_str(), additional)?;
+                let new_capacity = growth(len, additional);
+                let heap = HeapBuffer::with_new_capacity(self.as_str(), new_capacity)?;
                 *self = Repr::from_heap(heap);
             }
             Ok(())
@@ -227,7 +266,8 @@ impl Repr {
             // self is InlineBuffer
 
             if needed_capacity > MAX_INLINE_SIZE {
-                let heap = HeapBuffer::with_additional(self.as_str(), additional)?;
+                let new_capacity = growth(len, additional);
+                let heap = HeapBuffer::with_new_capacity(self.as_str(), new_capacity)?;
                 *self = Repr::from_heap(heap);
             } else {
                 // We have enough capacity, no need to reserve.
@@ -236,6 +276,17 @@ impl Repr {
         }
     }
 
+    /// Converts a short StaticBuffer to InlineBuffer. Other buffers are not changed.
+    #[inline]
+    pub(crate) fn compact(&mut self) {
+        if self.is_static_buffer() && self.len() <= MAX_INLINE_SIZE {
+            // SAFETY: We just checked that `len <= MAX_INLINE_SIZE`
+            let inline = unsafe { InlineBuffer::new(self.as_str()) };
+            // StaticBuffer doesn't need to be dropped.
+            *self = Repr::from_inline(inline);
+        }
+    }
+
     #[inline]
     pub(crate) fn shrink_to(&mut self, min_capacity: usize) -> Result<(), ReserveError> {
         // If the buffer is not heap allocated, we can't shrink it.
@@ -292,7 +343,7 @@ impl Repr {
             let str = heap.as_str();
             let additional = new_capacity - str.len();
             let new_heap = HeapBuffer::with_additional(str, additional)?;
-            *self = Repr::from_heap(new_heap);
+            self.replace_inner(Repr::from_heap(new_heap));
             Ok(())
         }
     }
@@ -305,6 +356,18 @@ impl Repr {
         let len = self.len();
         let str_len = string.len();
 
+        // Fast path: the result still fits in the inline buffer, so there is nothing to reserve.
+        // Heap and static markers are greater than any inline last byte.
+        if self.last_byte() < LastByte::HeapMarker as u8 && str_len <= MAX_INLINE_SIZE - len {
+            // SAFETY:
+            // - We just checked that `self` is InlineBuffer, so `len <= MAX_INLINE_SIZE`.
+            // - `len + str_len <= MAX_INLINE_SIZE`.
+            unsafe { self.as_inline_buffer_mut().push_str(len, string) };
+            return Ok(());
+        }
+
+        // `reserve` rejects `len + str_len` greater than the max capacity (including an overflow),
+        // so the arithmetic below can't overflow.
         self.reserve(str_len)?;
 
         let push_buffer = {
@@ -315,7 +378,7 @@ impl Repr {
             &mut slice[len..len + str_len]
         };
 
-        debug_assert_eq!(push_buffer.len(), string.as_bytes().len());
+        debug_assert_eq!(push_buffer.len(), string.len());
         push_buffer.copy_from_slice(string.as_bytes());
 
         // SAFETY:
@@ -329,6 +392,45 @@ impl Repr {
         Ok(())
     }
 
+    #[inline]
+    pub(crate) fn push_char(&mut self, ch: char) -> Result<(), ReserveError> {
+        let len = self.len();
+
+        // Fast path: the same as `push_str`, but writes `ch` without encoding it to a buffer first.
+        if self.last_byte() < LastByte::HeapMarker as u8 && ch.len_utf8() <= MAX_INLINE_SIZE - len {
+            // SAFETY:
+            // - We just checked that `self` is InlineBuffer, so `len <= MAX_INLINE_SIZE`.
+            // - `len + ch.len_utf8() <= MAX_INLINE_SIZE`.
+            unsafe { self.as_inline_buffer_mut().push_char(len, ch) };
+            return Ok(());
+        }
+
+        self.push_str(ch.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Appends `string` without checking the capacity and the uniqueness of the buffer.
+    ///
+    /// # Safety
+    /// - The buffer is not StaticBuffer
+    /// - If the buffer is HeapBuffer, it must be unique.
+    /// - `self.len() + string.len()` must be less than or equal to `self.capacity()`.
+    #[inline]
+    pub(crate) unsafe fn push_str_unchecked(&mut self, string: &str) {
+        let len = self.len();
+        let new_len = len + string.len();
+        debug_assert!(new_len <= self.capacity());
+
+        // SAFETY:
+        // - From `#Safety`, the buffer is modifiable and has enough capacity.
+        // - src (`string`) and dst (the buffer) don't overlap because the buffer is unique.
+        // - `0..new_len` is initialized with valid UTF-8 after copying.
+        unsafe {
+            let dst = self.as_slice_mut().as_mut_ptr().add(len);
+            ptr::copy_nonoverlapping(string.as_ptr(), dst, string.len());
+            self.set_len(new_len);
+        }
+    }
+
     #[inline]
     pub(crate) fn pop(&mut self) -> Result<Option<char>, ReserveError> {
         let ch = match self.as_str().chars().next_back() {
@@ -338,28 +440,32 @@ impl Repr {
 
         // SAFETY: We know this is a valid length which falls on a char boundary
         let new_len = self.len() - ch.len_utf8();
+        self.truncate(new_len)?;
+
+        Ok(Some(ch))
+    }
+
+    /// Shortens the string to `new_len` bytes.
+    ///
+    /// `new_len` must be less than or equal to the current length, and lie on a char boundary.
+    #[inline]
+    pub(crate) fn truncate(&mut self, new_len: usize) -> Result<(), ReserveError> {
+        debug_assert!(new_len <= self.len());
+        debug_assert!(self.as_str().is_char_boundary(new_len));
 
         if self.is_heap_buffer() {
             // SAFETY: We just checked that `self` is HeapBuffer
             let heap = unsafe { self.as_heap_buffer_mut() };
 
-            // See `reverse` method for the explanation of the ordering.
-            if heap.reference_count().fetch_sub(1, Release) == 1 {
-                // `heap` is unique, we can set the new length in place.
-
-                // See `reverse` method for the explanation of the ordering.
-                heap.reference_count().fetch_add(1, Acquire);
-
-                // SAFETY: `heap` is unique, we can reallocate in place.
+            // See `reserve` method for the explanation of the ordering.
+            if heap.is_unique() {
+                // SAFETY: `heap` is unique, we can set the new length in place.
                 unsafe { heap.set_len(new_len) };
             } else {
-                // SAFETY: `ptr` is valid for `len` bytes, and `HeapBuffer` contains valid UTF-8.
-                let str = unsafe {
-                    let ptr = self.0 as *mut u8;
-                    let slice = slice::from_raw_parts_mut(ptr, new_len);
-                    str::from_utf8_unchecked_mut(slice)
-                };
-                *self = Repr::from_str(str)?;
+                // SAFETY: `new_len` is on a char boundary (contracted by the caller).
+                let str = unsafe { heap.as_str().get_unchecked(..new_len) };
+                let next = Repr::from_str(str)?;
+                self.replace_inner(next);
             }
         } else if self.is_static_buffer() {
             // SAFETY:
@@ -369,11 +475,11 @@ impl Repr {
         } else {
             // SAFETY:
             // - The number of types of buffer is 3, and the remaining is InlineBuffer.
-            // - From `#Safety`, `new_len <= MAX_INLINE_SIZE` is true.
+            // - `new_len <= len <= MAX_INLINE_SIZE`
             unsafe { self.as_inline_buffer_mut().set_len(new_len) };
         }
 
-        Ok(Some(ch))
+        Ok(())
     }
 
     #[inline]
@@ -413,11 +519,12 @@ impl Repr {
         Ok(ch)
     }
 
+    /// Returns `true` if at least one character was removed.
     #[inline]
     pub(crate) fn retain(
         &mut self,
-        mut predicate: impl FnMut(char) -> bool,
-    ) -> Result<(), ReserveError> {
+        mut predicate: impl FnMut(usize, char) -> bool,
+    ) -> Result<bool, ReserveError> {
         // We will modify the buffer, we need to make sure it.
         self.ensure_modifiable()?;
 
@@ -438,7 +545,7 @@ impl Repr {
             let ch = unsafe { str.get_unchecked(g.src_idx..len).chars().next().unwrap_unchecked() };
             let ch_len = ch.len_utf8();
 
-            if predicate(ch) {
+            if predicate(g.src_idx, ch) {
                 // SAFETY: `g.dst_idx` represents a valid code points, don't split a char.
                 let dst_slice = unsafe {
                     let dst_ptr = str.as_mut_ptr().add(g.dst_idx);
@@ -458,9 +565,10 @@ impl Repr {
                 unsafe { self.self_.set_len(self.dst_idx) }
             }
         }
+        let removed = g.dst_idx != g.src_idx;
         drop(g);
 
-        Ok(())
+        Ok(removed)
     }
 
     #[inline]
@@ -495,6 +603,238 @@ impl Repr {
         Ok(())
     }
 
+    pub(crate) fn insert_char_repeated(
+        &mut self,
+        idx: usize,
+        ch: char,
+        n: usize,
+    ) -> Result<(), ReserveError> {
+        assert!(
+            self.as_str().is_char_boundary(idx),
+            "index is not a char boundary or out of bounds (index: {idx})",
+        );
+
+        let mut buf = [0; 4];
+        let ch = ch.encode_utf8(&mut buf).as_bytes();
+        let additional = ch.len().checked_mul(n).ok_or(ReserveError)?;
+        let new_len = self.len().checked_add(additional).ok_or(ReserveError)?;
+
+        // reserve makes self unique and modifiable
+        self.reserve(additional)?;
+        debug_assert!(self.is_unique());
+        debug_assert!(!self.is_static_buffer());
+
+        // SAFETY:
+        // - We contracted that we can split self at `idx`.
+        // - We just reserved enough capacity and set length after reserving.
+        // - The gap is filled by `n` copies of a valid UTF-8 encoded char.
+        unsafe {
+            // first move the tail to the new back, only once
+            let data = self.as_slice_mut().as_mut_ptr();
+            ptr::copy(data.add(idx), data.add(idx + additional), new_len - idx - additional);
+
+            // then fill the gap
+            let gap = data.add(idx);
+            if let [byte] = ch {
+                ptr::write_bytes(gap, *byte, n);
+            } else {
+                for i in 0..n {
+                    ptr::copy_nonoverlapping(ch.as_ptr(), gap.add(i * ch.len()), ch.len());
+                }
+            }
+
+            // and lastly resize the string
+            self.set_len(new_len);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn make_ascii_lowercase(&mut self) -> Result<(), ReserveError> {
+        // A unique buffer is converted directly, without scanning for an uppercase letter first.
+        if let Some(str) = self.as_unique_mut_str() {
+            // SAFETY: Changing the case of ASCII bytes keeps the string valid UTF-8, and non-ASCII
+            // bytes are left unchanged.
+            ascii_lowercase_in_place(unsafe { str.as_bytes_mut() });
+            return Ok(());
+        }
+
+        // Nothing to do, avoid making a copy of a shared or static buffer.
+        if !self.as_bytes().iter().any(u8::is_ascii_uppercase) {
+            return Ok(());
+        }
+
+        // We will modify the buffer, we need to make sure it.
+        self.ensure_modifiable()?;
+
+        // SAFETY:
+        // - We just made sure that the buffer is unique and modifiable (= not StaticBuffer).
+        // - Changing the case of ASCII bytes keeps the string valid UTF-8, and non-ASCII bytes are
+        //   left unchanged.
+        let bytes = unsafe { self.as_str_mut().as_bytes_mut() };
+        ascii_lowercase_in_place(bytes);
+        Ok(())
+    }
+
+    pub(crate) fn swap_ascii_case(&mut self) -> Result<(), ReserveError> {
+        // Nothing to do, avoid making a copy of a shared or static buffer.
+        if !self.as_bytes().iter().any(u8::is_ascii_alphabetic) {
+            return Ok(());
+        }
+
+        // We will modify the buffer, we need to make sure it.
+        self.ensure_modifiable()?;
+
+        // SAFETY:
+        // - We just made sure that the buffer is unique and modifiable (= not StaticBuffer).
+        // - Changing the case of ASCII bytes keeps the string valid UTF-8, and non-ASCII bytes are
+        //   left unchanged.
+        let bytes = unsafe { self.as_str_mut().as_bytes_mut() };
+        swap_ascii_case_in_place(bytes);
+        Ok(())
+    }
+
+    /// Overwrites all occurrences of `from` with `to`.
+    ///
+    /// `from` and `to` must be UTF-8 encoded chars of the same length.
+    pub(crate) fn replace_same_len(&mut self, from: &[u8], to: &[u8]) -> Result<(), ReserveError> {
+        debug_assert_eq!(from.len(), to.len());
+
+        // Nothing to do, avoid making a copy of a shared or static buffer.
+        let Some(first) = find_bytes(self.as_bytes(), from) else {
+            return Ok(());
+        };
+
+        // We will modify the buffer, we need to make sure it.
+        self.ensure_modifiable()?;
+
+        // SAFETY:
+        // - We just made sure that the buffer is unique and modifiable (= not StaticBuffer).
+        // - A whole char is replaced with another char of the same length, and UTF-8 is
+        //   self-synchronizing, so a match never starts in the middle of a char.
+        let bytes = unsafe { self.as_str_mut().as_bytes_mut() };
+        let mut idx = first;
+        loop {
+            bytes[idx..idx + to.len()].copy_from_slice(to);
+            idx += to.len();
+            match find_bytes(&bytes[idx..], from) {
+                Some(offset) => idx += offset,
+                None => return Ok(()),
+            }
+        }
+    }
+
+    pub(crate) fn reverse(&mut self) -> Result<(), ReserveError> {
+        // A string of at most one char is its own reverse, avoid making a copy of a shared or
+        // static buffer.
+        let mut chars = self.as_str().chars();
+        if chars.next().is_none() || chars.next().is_none() {
+            return Ok(());
+        }
+
+        // We will modify the buffer, we need to make sure it.
+        self.ensure_modifiable()?;
+
+        // SAFETY:
+        // - We just made sure that the buffer is unique and modifiable (= not StaticBuffer).
+        // - Reversing the chars keeps the string valid UTF-8. It is temporarily invalid inside
+        //   `reverse_chars_in_place`, but it can't panic in between.
+        let bytes = unsafe { self.as_str_mut().as_bytes_mut() };
+        reverse_chars_in_place(bytes);
+        Ok(())
+    }
+
+    #[inline]
+    pub(crate) fn as_mut_str(&mut self) -> Result<&mut str, ReserveError> {
+        // We will hand out a mutable str, we need to make sure it.
+        self.ensure_modifiable()?;
+
+        // SAFETY: We just made sure that the buffer is unique and modifiable (= not StaticBuffer).
+        Ok(unsafe { self.as_str_mut() })
+    }
+
+    #[inline]
+    pub(crate) fn as_unique_mut_str(&mut self) -> Option<&mut str> {
+        if self.is_static_buffer() || !self.is_unique() {
+            return None;
+        }
+
+        // SAFETY: We just checked that the buffer is unique and modifiable (= not StaticBuffer).
+        Some(unsafe { self.as_str_mut() })
+    }
+
+    #[inline]
+    pub(crate) fn spare_capacity_mut(&mut self) -> Result<&mut [MaybeUninit<u8>], ReserveError> {
+        // We will hand out a mutable buffer, we need to make sure it.
+        self.ensure_modifiable()?;
+
+        let len = self.len();
+
+        // SAFETY:
+        // - We just made sure that the buffer is unique and modifiable (= not StaticBuffer).
+        // - `len <= capacity`, so `len..` is in bounds.
+        // - `MaybeUninit<u8>` has the same layout as `u8`.
+        unsafe {
+            let spare = self.as_slice_mut().get_unchecked_mut(len..);
+            Ok(&mut *(spare as *mut [u8] as *mut [MaybeUninit<u8>]))
+        }
+    }
+
+    /// Returns whether the string contains `byte` if `self` is InlineBuffer, otherwise `None`.
+    #[inline]
+    pub(crate) fn inline_contains_byte(&self, byte: u8) -> Option<bool> {
+        if self.is_heap_buffer() || self.is_static_buffer() {
+            return None;
+        }
+        // SAFETY: The remaining type of buffer is InlineBuffer.
+        let inline = unsafe { self.as_inline_buffer() };
+        Some(inline.contains_byte(self.len(), byte))
+    }
+
+    /// Panics if any of the internal invariants of [`Repr`] is broken.
+    #[cfg(any(test, feature = "internal-debug"))]
+    pub(crate) fn assert_invariants(&self) {
+        let len = self.len();
+        let capacity = self.capacity();
+        assert!(len <= capacity, "length {len} exceeds capacity {capacity}");
+        assert!(str::from_utf8(self.as_bytes()).is_ok(), "buffer is not valid UTF-8");
+
+        if self.is_heap_buffer() {
+            assert!(capacity <= MAX_HEAP_CAPACITY, "capacity {capacity} is too large");
+            // SAFETY: We just checked that `self` is HeapBuffer
+            let count = unsafe { self.as_heap_buffer() }.reference_count().load(Acquire);
+            assert!(count >= 1, "reference count of a live HeapBuffer is 0");
+        } else if self.is_static_buffer() {
+            // The tag is the StaticMarker, and the length is checked above.
+        } else {
+            let last_byte = self.last_byte();
+            if len < MAX_INLINE_SIZE {
+                assert_eq!(
+                    last_byte,
+                    len as u8 | LastByte::MASK_1100_0000,
+                    "last byte doesn't match the inline length {len}",
+                );
+            } else {
+                // A full InlineBuffer ends with a data byte, which is never a tag.
+                assert_eq!(len, MAX_INLINE_SIZE);
+                assert!(last_byte < LastByte::MASK_1100_0000, "last byte {last_byte:#x} is a tag");
+            }
+        }
+    }
+
+    /// Returns the current reference count if `self` is HeapBuffer.
+    ///
+    /// The value may be outdated as soon as it is returned, so use this only for diagnostics.
+    #[inline]
+    pub(crate) fn reference_count(&self) -> Option<usize> {
+        if self.is_heap_buffer() {
+            // SAFETY: We just checked that `self` is HeapBuffer
+            let heap = unsafe { self.as_heap_buffer() };
+            Some(heap.reference_count().load(Relaxed))
+        } else {
+            None
+        }
+    }
+
     #[inline]
     pub(crate) fn is_unique(&self) -> bool {
         if self.is_heap_buffer() {
@@ -570,7 +910,7 @@ impl Repr {
     }
 
     #[inline(always)]
-    pub(crate) fn is_heap_buffer(&self) -> bool {
+    pub(crate) const fn is_heap_buffer(&self) -> bool {
         self.last_byte() == LastByte::HeapMarker as u8
     }
 
@@ -590,17 +930,12 @@ impl Repr {
             // SAFETY: we just checked self is HeapBuffer
             let heap = unsafe { self.as_heap_buffer_mut() };
 
-            // See `reverse` method for the explanation of the ordering.
-            if heap.reference_count().fetch_sub(1, Release) == 1 {
-                // `heap` is unique, we can modify it in place.
-
-                // See `reverse` method for the explanation of the ordering.
-                heap.reference_count().fetch_add(1, Acquire);
-            } else {
-                // SAFETY: `heap` is shared, we need to create a new buffer.
+            // See `reserve` method for the explanation of the ordering.
+            if !heap.is_unique() {
+                // `heap` is shared, we need to create a new buffer.
                 let str = heap.as_str();
                 let new_heap = HeapBuffer::new(str)?;
-                *self = Repr::from_heap(new_heap);
+                self.replace_inner(Repr::from_heap(new_heap));
             }
         } else if self.is_static_buffer() {
             // StaticBuffer is immutable, need to convert to other buffer.
@@ -695,6 +1030,12 @@ impl Repr {
         self.2 as u8
     }
 
+    #[inline(always)]
+    unsafe fn as_inline_buffer(&self) -> &InlineBuffer {
+        // SAFETY: A `Repr` is transmuted from `InlineBuffer`
+        &*(self as *const _ as *const InlineBuffer)
+    }
+
     #[inline(always)]
     unsafe fn as_inline_buffer_mut(&mut self) -> &mut InlineBuffer {
         // SAFETY: A `Repr` is transmuted from `InlineBuffer`
@@ -725,3 +1066,92 @@ impl Repr {
         &mut *(self as *mut _ as *mut StaticBuffer)
     }
 }
+
+/// Converts the ASCII uppercase letters in `bytes` to lowercase, leaving other bytes unchanged.
+///
+/// This folds a word at a time (SWAR, SIMD within a register) instead of byte by byte.
+fn ascii_lowercase_in_place(bytes: &mut [u8]) {
+    const USIZE_SIZE: usize = size_of::<usize>();
+    // 0x0101..01 and 0x8080..80
+    const LO: usize = usize::MAX / 0xFF;
+    const HI: usize = LO << 7;
+
+    let mut chunks = bytes.chunks_exact_mut(USIZE_SIZE);
+    for chunk in &mut chunks {
+        let mut word = [0; USIZE_SIZE];
+        word.copy_from_slice(chunk);
+        let x = usize::from_ne_bytes(word);
+        // Each byte is at most 0x7F after clearing the high bit, so the additions below never
+        // carry into the next byte.
+        let low7 = x & !HI;
+        // The high bit is set for the bytes `>= b'A'` and for the bytes `> b'Z'` respectively.
+        let ge_a = low7 + LO * (0x80 - b'A' as usize);
+        let gt_z = low7 + LO * (0x80 - b'Z' as usize - 1);
+        // Non-ASCII bytes (`!x` clears their high bit) are not letters.
+        let upper = ge_a & !gt_z & !x & HI;
+        // 0x80 >> 2 == 0x20, the difference between upper and lower case.
+        chunk.copy_from_slice(&(x | (upper >> 2)).to_ne_bytes());
+    }
+    chunks.into_remainder().make_ascii_lowercase();
+}
+
+/// Swaps the case of the ASCII letters in `bytes`, leaving other bytes unchanged.
+///
+/// Like [`ascii_lowercase_in_place`], this works a word at a time.
+fn swap_ascii_case_in_place(bytes: &mut [u8]) {
+    const USIZE_SIZE: usize = size_of::<usize>();
+    // 0x0101..01 and 0x8080..80
+    const LO: usize = usize::MAX / 0xFF;
+    const HI: usize = LO << 7;
+
+    let mut chunks = bytes.chunks_exact_mut(USIZE_SIZE);
+    for chunk in &mut chunks {
+        let mut word = [0; USIZE_SIZE];
+        word.copy_from_slice(chunk);
+        let x = usize::from_ne_bytes(word);
+        // Fold the case to lower by setting 0x20, each byte is still at most 0x7F, so the
+        // additions below never carry into the next byte.
+        let folded = (x & !HI) | (LO * 0x20);
+        // The high bit is set for the bytes `>= b'a'` and for the bytes `> b'z'` respectively.
+        let ge_a = folded + LO * (0x80 - b'a' as usize);
+        let gt_z = folded + LO * (0x80 - b'z' as usize - 1);
+        // Non-ASCII bytes (`!x` clears their high bit) are not letters.
+        let letter = ge_a & !gt_z & !x & HI;
+        // 0x80 >> 2 == 0x20, the difference between upper and lower case.
+        chunk.copy_from_slice(&(x ^ (letter >> 2)).to_ne_bytes());
+    }
+    for byte in chunks.into_remainder() {
+        if byte.is_ascii_alphabetic() {
+            *byte ^= 0x20;
+        }
+    }
+}
+
+/// Reverses the order of the UTF-8 encoded chars in `bytes`, keeping the bytes of each char.
+///
+/// This reverses all the bytes first, then reverses each char back, so no scratch buffer is needed.
+fn reverse_chars_in_place(bytes: &mut [u8]) {
+    bytes.reverse();
+
+    // Now the continuation bytes of each char come before its leading byte.
+    let mut start = 0;
+    while start < bytes.len() {
+        let mut end = start;
+        while end < bytes.len() && bytes[end] & 0xC0 == 0x80 {
+            end += 1;
+        }
+        // `end` is the leading byte, or out of bounds only if `bytes` was not valid UTF-8.
+        let end = (end + 1).min(bytes.len());
+        bytes[start..end].reverse();
+        start = end;
+    }
+}
+
+/// Returns the index of the first occurrence of the non-empty `needle` in `haystack`.
+#[inline]
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    match needle {
+        [byte] => haystack.iter().position(|b| b == byte),
+        _ => haystack.windows(needle.len()).position(|window| window == needle),
+    }
+}
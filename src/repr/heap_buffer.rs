@@ -10,11 +10,28 @@ use loom::sync::atomic::AtomicUsize;
 use internal::TextSize;
 
 /// [`HeapBuffer`] grows at an amortized rates of 1.5x
+///
+/// The amortized capacity is capped by the max capacity of [`HeapBuffer`], so that the growth
+/// doesn't fail when only the amortized part exceeds the limit.
+///
+/// The arithmetic saturates instead of overflowing. If the required capacity itself exceeds the
+/// limit, it is returned as is, and rejected by the allocation with a [`ReserveError`].
 #[inline(always)]
 pub(crate) fn amortized_growth(cur_len: usize, additional: usize) -> usize {
     let required = cur_len.saturating_add(additional);
     let amortized = cur_len.saturating_mul(3) / 2;
-    amortized.max(required)
+    amortized.min(TextSize::MAX).max(required)
+}
+
+/// [`HeapBuffer`] grows to the next power of two of the required capacity.
+///
+/// This is used by `LeanString::reserve_pow2`, and is capped and saturates in the same way as
+/// [`amortized_growth`].
+#[inline(always)]
+pub(crate) fn pow2_growth(cur_len: usize, additional: usize) -> usize {
+    let required = cur_len.saturating_add(additional);
+    let pow2 = required.checked_next_power_of_two().unwrap_or(usize::MAX);
+    pow2.min(TextSize::MAX).max(required)
 }
 
 #[repr(C)]
@@ -22,10 +39,11 @@ pub(super) struct HeapBuffer {
     // | Header | Data (array of `u8`) |
     //          ^ ptr
     ptr: NonNull<u8>,
+    // Unused word to place `len` at the last word of `Repr` (only with `wide_inline`).
+    _padding: [usize; REPR_WORDS - 2],
     len: TextSize,
 }
 
-#[cfg(target_pointer_width = "64")]
 struct Header {
     count: AtomicUsize,
     capacity: usize,
@@ -39,7 +57,9 @@ fn _static_assert() {
 }
 
 impl HeapBuffer {
-    #[cfg(target_pointer_width = "64")]
+    /// The maximum length/capacity of a [`HeapBuffer`].
+    pub(super) const MAX_CAPACITY: usize = TextSize::MAX;
+
     pub(super) fn new(text: &str) -> Result<Self, ReserveError> {
         let text_len = text.len();
 
@@ -53,24 +73,28 @@ impl HeapBuffer {
         // - src and dst don't overlap because we allocated dst just now.
         unsafe { ptr::copy_nonoverlapping(text.as_ptr(), ptr.as_ptr(), text_len) };
 
-        Ok(HeapBuffer { ptr, len })
+        Ok(HeapBuffer { ptr, _padding: [0; REPR_WORDS - 2], len })
     }
 
-    #[cfg(target_pointer_width = "64")]
     pub(crate) fn with_capacity(capacity: usize) -> Result<Self, ReserveError> {
         let len = TextSize::new(0)?;
         let ptr = HeapBuffer::allocate_ptr(capacity)?;
-        Ok(HeapBuffer { ptr, len })
+        Ok(HeapBuffer { ptr, _padding: [0; REPR_WORDS - 2], len })
     }
 
     pub(super) fn with_additional(text: &str, additional: usize) -> Result<Self, ReserveError> {
+        HeapBuffer::with_new_capacity(text, amortized_growth(text.len(), additional))
+    }
+
+    /// Creates a [`HeapBuffer`] with a copy of `text` and `new_capacity` capacity.
+    ///
+    /// `new_capacity` must be greater than or equal to `text.len()`.
+    pub(super) fn with_new_capacity(text: &str, new_capacity: usize) -> Result<Self, ReserveError> {
+        debug_assert!(new_capacity >= text.len());
         let text_len = text.len();
 
         let len = TextSize::new(text_len)?;
-        let ptr = {
-            let new_capacity = amortized_growth(text_len, additional);
-            HeapBuffer::allocate_ptr(new_capacity)?
-        };
+        let ptr = HeapBuffer::allocate_ptr(new_capacity)?;
 
         // SAFETY:
         // - src (`text`) and dst (`ptr`) is valid for `text_len` bytes because `text_len` comes
@@ -80,7 +104,7 @@ impl HeapBuffer {
         // - src and dst don't overlap because we allocated dst just now.
         unsafe { ptr::copy_nonoverlapping(text.as_ptr(), ptr.as_ptr(), text_len) };
 
-        Ok(HeapBuffer { ptr, len })
+        Ok(HeapBuffer { ptr, _padding: [0; REPR_WORDS - 2], len })
     }
 
     pub(super) fn capacity(&self) -> usize {
@@ -118,6 +142,11 @@ impl HeapBuffer {
             }
         };
 
+        // See `layout_from_capacity` for the reason of this limit.
+        if new_capacity > TextSize::MAX {
+            return Err(ReserveError);
+        }
+
         const ALLOC_LIMIT: usize = (isize::MAX as usize + 1) - HeapBuffer::align();
         let new_alloc_size = size_of::<Header>().saturating_add(new_capacity);
         if new_alloc_size > ALLOC_LIMIT {
@@ -130,11 +159,20 @@ impl HeapBuffer {
         // - `new_alloc_size` is greater than zero.
         // - `new_alloc_size` is ensured not to overflow when rounded up to the nearest multiple of
         //    alignment by `ALLOC_LIMIT`.
+        #[cfg(feature = "alloc-hooks")]
+        let old_capacity = self.header().capacity;
+
         let allocation = unsafe { realloc(self.allocation(), cur_layout, new_alloc_size) };
         if allocation.is_null() {
             return Err(ReserveError);
         }
 
+        #[cfg(feature = "alloc-hooks")]
+        crate::features::alloc_hooks::notify(crate::AllocEvent::Reallocate {
+            old_capacity,
+            new_capacity,
+        });
+
         // SAFETY:
         // - `allocation` is non-null.
         // - the allocation size is larger than or equal to the size of Header.
@@ -168,6 +206,12 @@ impl HeapBuffer {
                 unsafe { hint::unreachable_unchecked() }
             }
         };
+
+        #[cfg(feature = "alloc-hooks")]
+        crate::features::alloc_hooks::notify(crate::AllocEvent::Deallocate {
+            capacity: self.header().capacity,
+        });
+
         dealloc(self.allocation(), layout);
     }
 
@@ -194,7 +238,6 @@ impl HeapBuffer {
     /// # Safety
     /// - `len` bytes in the buffer must be valid UTF-8.
     /// - buffer is unique.
-    #[cfg(target_pointer_width = "64")]
     pub(super) unsafe fn set_len(&mut self, len: usize) {
         debug_assert!(self.is_unique());
         self.len = match TextSize::new(len) {
@@ -219,6 +262,9 @@ impl HeapBuffer {
             return Err(ReserveError);
         }
 
+        #[cfg(feature = "alloc-hooks")]
+        crate::features::alloc_hooks::notify(crate::AllocEvent::Allocate { capacity });
+
         // SAFETY:
         // - allocation is non-null.
         // - allocation size is larger than or equal to the size of Header.
@@ -230,6 +276,11 @@ impl HeapBuffer {
     }
 
     fn layout_from_capacity(capacity: usize) -> Result<Layout, ReserveError> {
+        // The length can't be larger than the capacity, so limiting the capacity ensures that the
+        // length always fits in `TextSize`.
+        if capacity > TextSize::MAX {
+            return Err(ReserveError);
+        }
         let alloc_size = size_of::<Header>().checked_add(capacity).ok_or(ReserveError)?;
         let align = HeapBuffer::align();
         Layout::from_size_align(alloc_size, align).map_err(
@@ -291,10 +342,10 @@ mod internal {
     /// - (on 32-bit architecture) 2^24 - 2 = 16777214          ≈ 16 MiB
     ///
     /// Practically speaking, on 64-bit architecture, this max value is enough for the
-    /// length/capacity of a HeapBuffer. However, it is not enough for 32-bit architectures, and if
-    /// more than 3 bytes are needed, the length/capacity must be switched to be stored using the
-    /// heap. Therefore, on 32-bit architecture, we use 2^24 - 2 as the maximum value, and 2^24 - 1
-    /// as the tag that indicates the length/capacity is stored in the heap.
+    /// length/capacity of a HeapBuffer. On 32-bit architecture, it limits a heap allocated string
+    /// to 2^24 - 2 bytes. There is no fallback for larger strings: a length or capacity above
+    /// [`TextSize::MAX`] is rejected with [`ReserveError`] (see `HeapBuffer::layout_from_capacity`),
+    /// and is never truncated.
     #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
     pub(super) struct TextSize(usize);
 
@@ -302,19 +353,23 @@ mod internal {
 
     impl TextSize {
         #[cfg(target_pointer_width = "64")]
-        const MAX: usize = {
+        pub(super) const MAX: usize = {
             let mut bytes = [255; USIZE_SIZE];
             bytes[USIZE_SIZE - 1] = 0;
             usize::from_le_bytes(bytes)
         };
 
+        /// 2^24 - 2 bytes, just below the max value of 3 bytes. Larger sizes are rejected with
+        /// [`ReserveError`].
+        #[cfg(target_pointer_width = "32")]
+        pub(super) const MAX: usize = (1 << 24) - 2;
+
         const TAG: usize = {
             let mut bytes = [0; USIZE_SIZE];
             bytes[USIZE_SIZE - 1] = LastByte::HeapMarker as u8;
             usize::from_ne_bytes(bytes)
         };
 
-        #[cfg(target_pointer_width = "64")]
         pub(super) const fn new(size: usize) -> Result<Self, ReserveError> {
             if size > Self::MAX {
                 return Err(ReserveError);
@@ -322,7 +377,6 @@ mod internal {
             Ok(TextSize(size.to_le() | Self::TAG))
         }
 
-        #[cfg(target_pointer_width = "64")]
         pub(super) fn as_usize(self) -> usize {
             let size = self.0 ^ Self::TAG;
             let bytes = size.to_ne_bytes();
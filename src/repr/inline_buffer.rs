@@ -4,6 +4,10 @@ use super::*;
 #[repr(C, align(8))]
 pub(super) struct InlineBuffer([u8; MAX_INLINE_SIZE]);
 
+#[cfg(target_pointer_width = "32")]
+#[repr(C, align(4))]
+pub(super) struct InlineBuffer([u8; MAX_INLINE_SIZE]);
+
 impl InlineBuffer {
     /// # Safety
     /// `text` must have a length less than or equal to `MAX_INLINE_SIZE`.
@@ -31,6 +35,40 @@ impl InlineBuffer {
         Self(buffer)
     }
 
+    /// Returns `true` if the first `len` bytes of the buffer contain `byte`.
+    ///
+    /// This compares a word at a time (SWAR, SIMD within a register) instead of byte by byte.
+    #[inline]
+    pub(super) fn contains_byte(&self, len: usize, byte: u8) -> bool {
+        const USIZE_SIZE: usize = size_of::<usize>();
+        // 0x0101..01 and 0x8080..80
+        const LO: usize = usize::MAX / 0xFF;
+        const HI: usize = LO << 7;
+
+        let pattern = LO * byte as usize;
+        for (i, chunk) in self.0.chunks_exact(USIZE_SIZE).enumerate() {
+            let mut bytes = [0; USIZE_SIZE];
+            bytes.copy_from_slice(chunk);
+            // The bytes equal to `byte` become 0.
+            let x = usize::from_le_bytes(bytes) ^ pattern;
+            // The high bit is set for the lowest zero byte, and possibly for the bytes above it
+            // (a false positive caused by the borrow), so the lowest set bit is always correct.
+            let found = x.wrapping_sub(LO) & !x & HI;
+
+            // Ignore the bytes after `len` (including the last byte which holds the length).
+            let valid = len.saturating_sub(i * USIZE_SIZE);
+            let mask = match valid {
+                0 => return false,
+                n if n >= USIZE_SIZE => usize::MAX,
+                n => (1 << (n * 8)) - 1,
+            };
+            if found & mask != 0 {
+                return true;
+            }
+        }
+        false
+    }
+
     /// # Safety
     /// - `len` bytes in the buffer must be valid UTF-8.
     /// - `len` must be less than or equal to `MAX_INLINE_SIZE`.
@@ -41,4 +79,49 @@ impl InlineBuffer {
             self.0[MAX_INLINE_SIZE - 1] = len as u8 | LastByte::MASK_1100_0000;
         }
     }
+
+    /// Appends `string` after the first `len` bytes, and sets the length.
+    ///
+    /// # Safety
+    /// - `len` must be the current length of the buffer.
+    /// - `len + string.len()` must be less than or equal to `MAX_INLINE_SIZE`.
+    #[inline]
+    pub(super) unsafe fn push_str(&mut self, len: usize, string: &str) {
+        let new_len = len + string.len();
+        debug_assert!(new_len <= MAX_INLINE_SIZE);
+
+        // SAFETY:
+        // - From `#Safety`, `len..new_len` is in bounds.
+        // - src (`string`) and dst (`self`) don't overlap because we own dst.
+        // - `0..new_len` is valid UTF-8 after copying.
+        unsafe {
+            let dst = self.0.as_mut_ptr().add(len);
+            ptr::copy_nonoverlapping(string.as_ptr(), dst, string.len());
+            self.set_len(new_len);
+        }
+    }
+
+    /// Appends `ch` after the first `len` bytes, and sets the length.
+    ///
+    /// # Safety
+    /// - `len` must be the current length of the buffer.
+    /// - `len + ch.len_utf8()` must be less than or equal to `MAX_INLINE_SIZE`.
+    #[inline]
+    pub(super) unsafe fn push_char(&mut self, len: usize, ch: char) {
+        let new_len = len + ch.len_utf8();
+        debug_assert!(new_len <= MAX_INLINE_SIZE);
+
+        // A single byte store for ASCII, instead of copying the encoded bytes with a variable
+        // length.
+        if ch.is_ascii() {
+            // SAFETY: From `#Safety`, `len` is in bounds.
+            unsafe { *self.0.get_unchecked_mut(len) = ch as u8 };
+        } else {
+            // SAFETY: From `#Safety`, `len..new_len` is in bounds.
+            ch.encode_utf8(unsafe { self.0.get_unchecked_mut(len..new_len) });
+        }
+
+        // SAFETY: `0..new_len` is valid UTF-8 after writing `ch`.
+        unsafe { self.set_len(new_len) };
+    }
 }
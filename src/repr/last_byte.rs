@@ -226,17 +226,30 @@ pub enum LastByte {
     Length14 = 0xCE,
     Length15 = 0xCF,
 
-    HeapMarker = 0xD0,
-    StaticMarker = 0xD1,
+    // With `wide_inline`, the length of the InlineBuffer is up to 24 bytes, and the markers are
+    // placed right after the lengths.
+    #[cfg(feature = "wide_inline")] Length16 = 0xD0,
+    #[cfg(feature = "wide_inline")] Length17 = 0xD1,
+    #[cfg(feature = "wide_inline")] Length18 = 0xD2,
+    #[cfg(feature = "wide_inline")] Length19 = 0xD3,
+    #[cfg(feature = "wide_inline")] Length20 = 0xD4,
+    #[cfg(feature = "wide_inline")] Length21 = 0xD5,
+    #[cfg(feature = "wide_inline")] Length22 = 0xD6,
+    #[cfg(feature = "wide_inline")] Length23 = 0xD7,
 
-    #[cfg(feature = "last_byte")] Unused00_1101_0010 = 0xD2,
-    #[cfg(feature = "last_byte")] Unused01_1101_0011 = 0xD3,
-    #[cfg(feature = "last_byte")] Unused02_1101_0100 = 0xD4,
-    #[cfg(feature = "last_byte")] Unused03_1101_0101 = 0xD5,
-    #[cfg(feature = "last_byte")] Unused04_1101_0110 = 0xD6,
-    #[cfg(feature = "last_byte")] Unused05_1101_0111 = 0xD7,
-    #[cfg(feature = "last_byte")] Unused06_1101_1000 = 0xD8,
-    #[cfg(feature = "last_byte")] Unused07_1101_1001 = 0xD9,
+    #[cfg(not(feature = "wide_inline"))] HeapMarker = 0xD0,
+    #[cfg(not(feature = "wide_inline"))] StaticMarker = 0xD1,
+    #[cfg(feature = "wide_inline")] HeapMarker = 0xD8,
+    #[cfg(feature = "wide_inline")] StaticMarker = 0xD9,
+
+    #[cfg(all(feature = "last_byte", not(feature = "wide_inline")))] Unused00_1101_0010 = 0xD2,
+    #[cfg(all(feature = "last_byte", not(feature = "wide_inline")))] Unused01_1101_0011 = 0xD3,
+    #[cfg(all(feature = "last_byte", not(feature = "wide_inline")))] Unused02_1101_0100 = 0xD4,
+    #[cfg(all(feature = "last_byte", not(feature = "wide_inline")))] Unused03_1101_0101 = 0xD5,
+    #[cfg(all(feature = "last_byte", not(feature = "wide_inline")))] Unused04_1101_0110 = 0xD6,
+    #[cfg(all(feature = "last_byte", not(feature = "wide_inline")))] Unused05_1101_0111 = 0xD7,
+    #[cfg(all(feature = "last_byte", not(feature = "wide_inline")))] Unused06_1101_1000 = 0xD8,
+    #[cfg(all(feature = "last_byte", not(feature = "wide_inline")))] Unused07_1101_1001 = 0xD9,
     #[cfg(feature = "last_byte")] Unused08_1101_1010 = 0xDA,
     #[cfg(feature = "last_byte")] Unused09_1101_1011 = 0xDB,
     #[cfg(feature = "last_byte")] Unused10_1101_1100 = 0xDC,
@@ -311,6 +324,10 @@ mod tests {
             00, 01, 02, 03, 04, 05, 06, 07,
             08, 09, 10, 11, 12, 13, 14, 15,
         }
+        #[cfg(feature = "wide_inline")]
+        gen_case! {
+            16, 17, 18, 19, 20, 21, 22, 23,
+        }
     }
 
     #[test]
@@ -324,6 +341,7 @@ mod tests {
                 }
             )*};
         }
+        #[cfg(not(feature = "wide_inline"))]
         gen_case! {
             Unused00 1101_0010,
             Unused01 1101_0011,
@@ -333,6 +351,8 @@ mod tests {
             Unused05 1101_0111,
             Unused06 1101_1000,
             Unused07 1101_1001,
+        }
+        gen_case! {
             Unused08 1101_1010,
             Unused09 1101_1011,
             Unused10 1101_1100,
@@ -142,6 +142,18 @@ impl_NumToRepr_for_integers!(
     as u64
 );
 
+#[cfg(target_pointer_width = "32")]
+impl_NumToRepr_for_integers!(
+    i8, u8, i16, u16, i32, u32, isize, usize;
+    as u32
+);
+
+#[cfg(target_pointer_width = "32")]
+impl_NumToRepr_for_integers!(
+    i64, u64;
+    as u64
+);
+
 // NOTE: ZeroablePrimitive is unstable
 macro_rules! impl_IntoRepr_for_nonzero_integers {
     ($($itype:ty),* $(,)?) => {$(
@@ -361,3 +373,17 @@ impl DigitCount for isize {
         DigitCount::digit_count(self as i64)
     }
 }
+
+#[cfg(target_pointer_width = "32")]
+impl DigitCount for usize {
+    fn digit_count(self) -> usize {
+        DigitCount::digit_count(self as u32)
+    }
+}
+
+#[cfg(target_pointer_width = "32")]
+impl DigitCount for isize {
+    fn digit_count(self) -> usize {
+        DigitCount::digit_count(self as i32)
+    }
+}
@@ -3,11 +3,20 @@ use super::*;
 #[repr(C)]
 pub(super) struct StaticBuffer {
     ptr: ptr::NonNull<u8>,
+    // Unused word to place `len` at the last word of `Repr` (only with `wide_inline`).
+    _padding: [usize; REPR_WORDS - 2],
     len: usize, // stored as little-endian
 }
 
 const USIZE_SIZE: usize = size_of::<usize>();
 
+fn _static_assert() {
+    const {
+        assert!(size_of::<StaticBuffer>() == MAX_INLINE_SIZE);
+        assert!(align_of::<StaticBuffer>() == align_of::<usize>());
+    }
+}
+
 impl StaticBuffer {
     const MAX_LENGTH: usize = {
         let mut bytes = [255; USIZE_SIZE];
@@ -32,7 +41,7 @@ impl StaticBuffer {
         // SAFETY: `&'static str` must have a non-null, properly aligned address
         let ptr = unsafe { ptr::NonNull::new_unchecked(text.as_ptr() as *mut _) };
 
-        Ok(Self { ptr, len })
+        Ok(Self { ptr, _padding: [0; REPR_WORDS - 2], len })
     }
 
     pub(super) fn len(&self) -> usize {
@@ -41,6 +50,14 @@ impl StaticBuffer {
         usize::from_le_bytes(bytes)
     }
 
+    pub(super) fn as_str(&self) -> &'static str {
+        // SAFETY: The buffer is created from a `&'static str` with `len` bytes.
+        unsafe {
+            let bytes = slice::from_raw_parts(self.ptr.as_ptr(), self.len());
+            str::from_utf8_unchecked(bytes)
+        }
+    }
+
     /// # Safety
     /// `len` bytes in the buffer must be valid UTF-8.
     pub(super) unsafe fn set_len(&mut self, len: usize) {
@@ -0,0 +1,82 @@
+use crate::{LeanString, UnwrapWithMsg};
+use core::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+/// A guard which restores the length of a [`LeanString`] when dropped.
+///
+/// This struct is created by [`LeanString::with_saved_len()`]. It dereferences to the
+/// [`LeanString`], so you can append a temporary suffix, use it, and let the guard drop it again.
+/// The capacity is retained, so building suffixes in a loop doesn't reallocate.
+///
+/// If the [`LeanString`] became shorter than the saved length, or the saved length no longer lies
+/// on a [`char`] boundary, it is left as it is.
+///
+/// # Panics
+///
+/// Dropping the guard panics if the [`LeanString`] is shared (e.g. it was cloned while the guard
+/// was alive) and the system is out-of-memory when cloning it.
+///
+/// # Examples
+///
+/// ```
+/// # use lean_string::LeanString;
+/// let mut path = LeanString::from("/usr/local/");
+///
+/// for name in ["bin", "lib", "share"] {
+///     let mut scoped = path.with_saved_len();
+///     scoped.push_str(name);
+///     assert!(scoped.starts_with("/usr/local/"));
+///     assert!(scoped.ends_with(name));
+/// }
+/// assert_eq!(path, "/usr/local/");
+/// ```
+pub struct ScopedLen<'a> {
+    string: &'a mut LeanString,
+    len: usize,
+}
+
+impl<'a> ScopedLen<'a> {
+    #[inline]
+    pub(crate) fn new(string: &'a mut LeanString) -> Self {
+        let len = string.len();
+        ScopedLen { string, len }
+    }
+
+    /// Returns the length which is restored when the guard is dropped.
+    #[inline]
+    pub fn saved_len(&self) -> usize {
+        self.len
+    }
+}
+
+impl Deref for ScopedLen<'_> {
+    type Target = LeanString;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.string
+    }
+}
+
+impl DerefMut for ScopedLen<'_> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.string
+    }
+}
+
+impl Drop for ScopedLen<'_> {
+    fn drop(&mut self) {
+        if self.len < self.string.len() && self.string.is_char_boundary(self.len) {
+            self.string.0.truncate(self.len).unwrap_with_msg();
+        }
+    }
+}
+
+impl fmt::Debug for ScopedLen<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScopedLen").field("string", &self.string).field("len", &self.len).finish()
+    }
+}
@@ -61,6 +61,15 @@ impl<T: fmt::Display> ToLeanString for T {
     }
 }
 
+// NOTE: `castaway` can't match `&&str` because it is not `'static`, so string slices get a dedicated
+// impl instead. It doesn't overlap with the above impl because `str` is not `Sized`.
+impl ToLeanString for str {
+    #[inline]
+    fn try_to_lean_string(&self) -> Result<LeanString, ToLeanStringError> {
+        Ok(LeanString(Repr::from_str(self)?))
+    }
+}
+
 // SAFETY:
 // - `LeanString` is `'static`.
 // - `LeanString` does not contain any lifetime parameter.
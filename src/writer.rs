@@ -0,0 +1,76 @@
+use crate::{LeanString, ReserveError};
+use core::fmt;
+
+/// A [`fmt::Write`] adapter for [`LeanString`] which captures the allocation error.
+///
+/// `impl fmt::Write for LeanString` panics if the system is out-of-memory. [`FallibleWriter`]
+/// instead stores the first [`ReserveError`] and returns [`fmt::Error`], so that you can use the
+/// [`write!`] macro and handle the error later by [`FallibleWriter::into_result()`].
+///
+/// # Examples
+///
+/// ```
+/// # use lean_string::{FallibleWriter, LeanString};
+/// use core::fmt::Write;
+///
+/// let mut s = LeanString::new();
+/// let mut writer = FallibleWriter::new(&mut s);
+/// let _ = write!(writer, "{} + {} = {}", 1, 2, 1 + 2);
+/// assert!(writer.into_result().is_ok());
+///
+/// assert_eq!(s, "1 + 2 = 3");
+/// ```
+pub struct FallibleWriter<'a> {
+    string: &'a mut LeanString,
+    error: Option<ReserveError>,
+}
+
+impl<'a> FallibleWriter<'a> {
+    /// Creates a new [`FallibleWriter`] which appends to `string`.
+    #[inline]
+    pub fn new(string: &'a mut LeanString) -> Self {
+        FallibleWriter { string, error: None }
+    }
+
+    /// Returns the first [`ReserveError`] that occurred while writing, if any.
+    #[inline]
+    pub fn into_result(self) -> Result<(), ReserveError> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Write for FallibleWriter<'_> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.error.is_some() {
+            return Err(fmt::Error);
+        }
+        self.string.try_push_str(s).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        if self.error.is_some() {
+            return Err(fmt::Error);
+        }
+        self.string.try_push(c).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+impl fmt::Debug for FallibleWriter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FallibleWriter")
+            .field("string", &self.string)
+            .field("error", &self.error)
+            .finish()
+    }
+}
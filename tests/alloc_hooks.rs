@@ -0,0 +1,78 @@
+#![cfg(feature = "alloc-hooks")]
+
+use lean_string::{set_alloc_observer, AllocEvent, LeanString};
+use std::cell::RefCell;
+
+thread_local! {
+    static EVENTS: RefCell<Vec<AllocEvent>> = const { RefCell::new(Vec::new()) };
+}
+
+// The observer is global, but the events are reported on the allocating thread, so each test
+// only sees its own events.
+fn record(event: AllocEvent) {
+    EVENTS.with(|events| events.borrow_mut().push(event));
+}
+
+fn take_events() -> Vec<AllocEvent> {
+    EVENTS.with(|events| events.take())
+}
+
+#[test]
+fn observes_crossing_inline_boundary() {
+    set_alloc_observer(record);
+    take_events();
+
+    let text = "0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut s = LeanString::new();
+    for ch in text[..LeanString::INLINE_CAPACITY].chars() {
+        s.push(ch);
+    }
+    assert_eq!(take_events(), []);
+
+    s.push('!');
+    assert!(s.is_heap_allocated());
+    let capacity = s.capacity();
+    assert_eq!(take_events(), [AllocEvent::Allocate { capacity }]);
+
+    drop(s);
+    assert_eq!(take_events(), [AllocEvent::Deallocate { capacity }]);
+}
+
+#[test]
+fn observes_reallocation() {
+    set_alloc_observer(record);
+    take_events();
+
+    let mut s = LeanString::with_capacity(100);
+    s.push_str("abc");
+    s.reserve(200);
+    let new_capacity = s.capacity();
+    s.shrink_to(50);
+    assert_eq!(
+        take_events(),
+        [
+            AllocEvent::Allocate { capacity: 100 },
+            AllocEvent::Reallocate { old_capacity: 100, new_capacity },
+            AllocEvent::Reallocate { old_capacity: new_capacity, new_capacity: 50 },
+        ]
+    );
+
+    // a shared buffer is freed only once, by the last reference
+    let shared = s.clone();
+    drop(s);
+    assert_eq!(take_events(), []);
+    drop(shared);
+    assert_eq!(take_events(), [AllocEvent::Deallocate { capacity: 50 }]);
+}
+
+#[test]
+fn static_and_inline_strings_are_not_observed() {
+    set_alloc_observer(record);
+    take_events();
+
+    let s = LeanString::from_static_str("a static string, longer than inline");
+    let t = s.clone();
+    let u = LeanString::from("short");
+    drop((s, t, u));
+    assert_eq!(take_events(), []);
+}
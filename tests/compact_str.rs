@@ -0,0 +1,55 @@
+#![cfg(feature = "compact_str")]
+
+use compact_str::CompactString;
+use lean_string::LeanString;
+
+#[test]
+fn round_trip() {
+    let text = "0123456789abcdefghijklmnopqrstuvwxyz🦀";
+    for end in [0, 1, 10, 16, 24, 30, text.len()] {
+        let text = &text[..end];
+
+        let compact = CompactString::new(text);
+        let lean = LeanString::from(compact.clone());
+        assert_eq!(lean, text);
+        let back = CompactString::from(lean);
+        assert_eq!(back, compact);
+    }
+}
+
+#[test]
+fn short_strings_stay_inline() {
+    let lean = LeanString::from(CompactString::new("short"));
+    assert!(!lean.is_heap_allocated());
+
+    let compact = CompactString::from(LeanString::from("short"));
+    assert!(!compact.is_heap_allocated());
+
+    // full inline buffers on both sides
+    let text = &"0123456789abcdefghijklmnopqrstuvwxyz"[..LeanString::INLINE_CAPACITY];
+    let lean = LeanString::from(CompactString::new(text));
+    assert!(!lean.is_heap_allocated());
+    assert!(!CompactString::from(lean).is_heap_allocated());
+}
+
+#[test]
+fn long_strings_are_copied() {
+    let text = "a long string which is allocated on the heap on both sides";
+
+    let compact = CompactString::new(text);
+    let lean = LeanString::from(compact.clone());
+    assert!(lean.is_heap_allocated());
+    assert_ne!(lean.as_ptr(), compact.as_ptr());
+
+    let back = CompactString::from(lean.clone());
+    assert!(back.is_heap_allocated());
+    assert_ne!(back.as_ptr(), lean.as_ptr());
+}
+
+#[test]
+fn static_buffer_is_not_copied() {
+    let text: &'static str = "a static string which is longer than any inline buffer";
+    let compact = CompactString::from(LeanString::from_static_str(text));
+    assert_eq!(compact.as_static_str(), Some(text));
+    assert_eq!(compact.as_ptr(), text.as_ptr());
+}
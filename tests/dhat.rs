@@ -0,0 +1,447 @@
+// The heap profiler of dhat is process-wide and only one can run at a time, so this test uses a
+// custom harness (`harness = false` in Cargo.toml) and runs each test sequentially.
+
+use std::borrow::Cow;
+
+use lean_string::{LeanString, LeanStringBuilder};
+
+#[global_allocator]
+static ALLOC: dhat::Alloc = dhat::Alloc;
+
+macro_rules! test_heap {
+    ($(
+        fn $name:ident() $body:block
+    )*) => {
+        $(
+            fn $name() $body
+        )*
+
+        fn main() {
+            // dhat is too slow (and captures backtraces) under Miri.
+            if cfg!(miri) {
+                return;
+            }
+            $(
+                {
+                    let _profiler = dhat::Profiler::builder().testing().build();
+                    $name();
+                }
+                println!("test {} ... ok", stringify!($name));
+            )*
+        }
+    };
+}
+
+test_heap! {
+    fn swap_keeps_reference_count() {
+        let heap = LeanString::from("abcdefghijklmnopqrstuvwxyz");
+        let mut shared = heap.clone();
+        let mut inline = LeanString::from("inline");
+
+        shared.swap(&mut inline);
+        assert_eq!(shared, "inline");
+        assert_eq!(inline, "abcdefghijklmnopqrstuvwxyz");
+        assert_eq!(inline.as_ptr(), heap.as_ptr());
+
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.total_blocks, 1);
+        dhat::assert_eq!(stats.curr_blocks, 1);
+
+        // `inline` still holds a reference after `heap` is dropped.
+        drop(heap);
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.curr_blocks, 1);
+
+        drop(inline);
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.curr_blocks, 0);
+    }
+
+    fn builder_allocates_once() {
+        let mut builder = LeanStringBuilder::with_capacity(1000);
+        for i in 0..100 {
+            builder.push_str("012345678");
+            builder.push(char::from(b'a' + (i % 26) as u8));
+        }
+        let s = builder.finish();
+        assert_eq!(s.len(), 1000);
+        assert_eq!(s.capacity(), 1000);
+        assert!(s.starts_with("012345678a012345678b"));
+
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.total_blocks, 1);
+        dhat::assert_eq!(stats.total_bytes as usize, 1000 + 2 * size_of::<usize>());
+    }
+
+    fn builder_grows_beyond_capacity() {
+        let mut builder = LeanStringBuilder::with_capacity(20);
+        builder.push_str("01234567890123456789");
+        builder.push_str("overflow");
+        let s = builder.finish_and_shrink();
+        assert_eq!(s, "01234567890123456789overflow");
+        assert_eq!(s.capacity(), s.len());
+
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.curr_blocks, 1);
+    }
+
+    fn extend_from_strings_reserves_per_chunk() {
+        let pieces: Vec<String> = (0..16).map(|i| format!("piece-{i:04}")).collect();
+        let expected = pieces.concat();
+        let before = dhat::HeapStats::get();
+
+        let mut s = LeanString::new();
+        s.extend(pieces);
+        assert_eq!(s, expected);
+
+        // All pieces fit in one chunk, so only one buffer is allocated.
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.total_blocks - before.total_blocks, 1);
+    }
+
+    fn extend_from_boxed_strs_and_cows_reserves_per_chunk() {
+        let boxed: Vec<Box<str>> = (0..16).map(|i| format!("piece-{i:04}").into()).collect();
+        let cows: Vec<std::borrow::Cow<'_, str>> =
+            boxed.iter().map(|s| s.to_string().into()).collect();
+        let expected = boxed.concat();
+        let before = dhat::HeapStats::get();
+
+        let mut s = LeanString::new();
+        s.extend(boxed);
+        assert_eq!(s, expected);
+        let mut s = LeanString::new();
+        s.extend(cows);
+        assert_eq!(s, expected);
+
+        // All pieces fit in one chunk, so only one buffer is allocated for each.
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.total_blocks - before.total_blocks, 2);
+    }
+
+    fn symbol_table_allocations() {
+        // Identifiers of a typical compiler's symbol table, most of them are 16-24 bytes.
+        const SYMBOLS: &[&str] = &[
+            "parse_expression",
+            "parse_statement_list",
+            "resolve_type_alias",
+            "lower_match_arms",
+            "emit_function_prologue",
+            "TypeCheckContext",
+            "visit_generic_params",
+            "infer_closure_signature",
+            "x",
+            "len",
+            "DiagnosticBuilder",
+            "collect_trait_impls_for_crate",
+        ];
+        let mut table = Vec::with_capacity(SYMBOLS.len());
+        let before = dhat::HeapStats::get();
+
+        for symbol in SYMBOLS {
+            table.push(LeanString::from(*symbol));
+        }
+
+        let allocated = dhat::HeapStats::get().total_blocks - before.total_blocks;
+        let expected = SYMBOLS.iter().filter(|s| s.len() > size_of::<LeanString>()).count();
+        dhat::assert_eq!(allocated as usize, expected);
+        if cfg!(all(feature = "wide_inline", target_pointer_width = "64")) {
+            dhat::assert_eq!(allocated, 1);
+        }
+    }
+
+    fn into_static_str_reuses_static_buffer() {
+        let text: &'static str = "a long text which lives in a static buffer";
+        let leaked = LeanString::from_static_str(text).into_static_str();
+        assert_eq!(leaked.as_ptr(), text.as_ptr());
+
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.total_blocks, 0);
+
+        // an inline string is copied into a new (leaked) allocation
+        let leaked = LeanString::from("inline").into_static_str();
+        assert_eq!(leaked, "inline");
+
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.total_blocks, 1);
+        dhat::assert_eq!(stats.curr_blocks, 1);
+
+        // the heap buffer is released, only the copy is leaked
+        let leaked = LeanString::from(text).into_static_str();
+        assert_eq!(leaked, text);
+
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.total_blocks, 3);
+        dhat::assert_eq!(stats.curr_blocks, 2);
+    }
+
+    fn add_reuses_buffers() {
+        // empty lhs: the heap rhs is moved, not copied
+        let rhs = LeanString::from("abcdefghijklmnopqrstuvwxyz0123456789");
+        let ptr = rhs.as_ptr();
+        let sum = LeanString::new() + rhs;
+        assert_eq!(sum.as_ptr(), ptr);
+
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.total_blocks, 1);
+
+        // unique lhs with slack: appended in place
+        let mut lhs = LeanString::with_capacity(100);
+        lhs.push_str("abcdefghijklmnopqrstuvwxyz");
+        let ptr = lhs.as_ptr();
+        let sum = lhs + sum;
+        assert_eq!(sum.as_ptr(), ptr);
+        assert_eq!(sum, "abcdefghijklmnopqrstuvwxyzabcdefghijklmnopqrstuvwxyz0123456789");
+
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.total_blocks, 2);
+        dhat::assert_eq!(stats.curr_blocks, 1);
+    }
+
+    fn from_static_cow_borrowed_does_not_allocate() {
+        use std::borrow::Cow;
+
+        let text: &'static str = "a long text which lives in a static buffer";
+        let s = LeanString::from_static_cow(Cow::Borrowed(text));
+        assert_eq!(s.as_ptr(), text.as_ptr());
+
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.total_blocks, 0);
+
+        // the general `From<Cow>` copies it
+        let copied = LeanString::from(Cow::Borrowed(text));
+        assert_eq!(copied, text);
+
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.total_blocks, 1);
+    }
+
+    fn reserve_for_allocates_once() {
+        let pieces = ["alpha", "beta", "gamma", "delta", "epsilon", "zeta", "eta", "theta"];
+
+        let mut s = LeanString::new();
+        s.reserve_for(pieces.iter().map(|p| p.len()));
+        for piece in pieces {
+            s.push_str(piece);
+        }
+
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.total_blocks, 1);
+        assert_eq!(s, "alphabetagammadeltaepsilonzetaetatheta");
+    }
+
+    fn from_static_utf8_does_not_allocate() {
+        static BYTES: &[u8] = b"a long valid UTF-8 text which is embedded in the binary";
+
+        let s = LeanString::from_static_utf8(BYTES).unwrap();
+        assert_eq!(s.as_ptr(), BYTES.as_ptr());
+        let short = LeanString::from_static_utf8(&BYTES[..4]).unwrap();
+        assert!(LeanString::from_static_utf8(b"invalid \xFF").is_err());
+
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.total_blocks, 0);
+        assert_eq!(s, "a long valid UTF-8 text which is embedded in the binary");
+        assert_eq!(short, "a lo");
+    }
+
+    fn shrink_to_fit_inlines_and_frees() {
+        let text = "0123456789abcdefghijklmnopqrstuvwxyz";
+        let exact = &text[..LeanString::INLINE_CAPACITY];
+
+        let mut s = LeanString::with_capacity(100);
+        s.push_str(exact);
+        assert!(s.is_heap_allocated());
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.curr_blocks, 1);
+
+        s.shrink_to_fit();
+        assert!(!s.is_heap_allocated());
+        assert_eq!(s, exact);
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.total_blocks, 1);
+        dhat::assert_eq!(stats.curr_blocks, 0);
+
+        // a shared buffer is kept alive by the other reference
+        let mut s = LeanString::with_capacity(100);
+        s.push_str(exact);
+        let shared = s.clone();
+        s.shrink_to_fit();
+        assert!(!s.is_heap_allocated());
+        assert_eq!(s, shared);
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.curr_blocks, 1);
+        drop(shared);
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.curr_blocks, 0);
+    }
+
+    fn collect_chars_reserves_lower_bound_in_bytes() {
+        // The lower bound of `size_hint` is in chars, and it is reserved as bytes, because a char
+        // is at least 1 byte. This is exact for ASCII.
+        let s: LeanString = core::iter::repeat_n('a', 100).collect();
+        assert_eq!(s.capacity(), 100);
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.total_blocks, 1);
+    }
+
+    fn collect_wide_chars_reallocations() {
+        // For 4-byte chars, the lower bound covers only a quarter of the result, and the rest is
+        // left to the amortized (1.5x) growth: 100 -> 150 -> 222 -> 330 -> 492 bytes.
+        let s: LeanString = core::iter::repeat_n('🦀', 100).collect();
+        assert_eq!(s.capacity(), 492);
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.total_blocks, 5);
+
+        let mut extended = LeanString::new();
+        extended.extend(core::iter::repeat_n('🦀', 100));
+        assert_eq!(extended, s);
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.total_blocks, 10);
+    }
+
+    fn into_bytes_iter_releases_buffer_when_exhausted() {
+        let s = LeanString::from("a heap allocated string, longer than inline");
+        let mut bytes = s.into_bytes_iter();
+        let mut consumed = 0;
+        while bytes.next().is_some() {
+            consumed += 1;
+        }
+        assert_eq!(consumed, 43);
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.curr_blocks, 0);
+        drop(bytes);
+    }
+
+    fn push_cow_owned_into_empty_allocates_exactly() {
+        let owned = String::from("a heap allocated string, longer than inline");
+        let mut s = LeanString::new();
+        s.push_cow(owned.into());
+        assert_eq!(s, "a heap allocated string, longer than inline");
+        assert_eq!(s.capacity(), s.len());
+        // one for the `String`, which is freed after the copy, and one for the `LeanString`
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.total_blocks, 2);
+        dhat::assert_eq!(stats.curr_blocks, 1);
+    }
+
+    fn push_cow_owned_into_empty_heap_releases_old_buffer() {
+        let text = "x".repeat(100);
+
+        // unique, empty heap buffer
+        let mut s = LeanString::with_capacity(40);
+        s.push_cow(Cow::Owned(text.clone()));
+        assert_eq!(s, text);
+        let stats = dhat::HeapStats::get();
+        // `text` and `s`
+        dhat::assert_eq!(stats.curr_blocks, 2);
+        drop(s);
+
+        // cleared heap string
+        let mut s = LeanString::from(text.as_str());
+        s.clear();
+        s.push_cow(Cow::Owned("y".repeat(200)));
+        assert_eq!(s, "y".repeat(200));
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.curr_blocks, 2);
+        drop(s);
+
+        // shared, empty heap buffer
+        let original = LeanString::with_capacity(40);
+        let mut shared = original.clone();
+        shared.push_cow(Cow::Owned(text.clone()));
+        assert_eq!(shared, text);
+        assert!(original.is_empty());
+        let stats = dhat::HeapStats::get();
+        // `text`, `original` and `shared`
+        dhat::assert_eq!(stats.curr_blocks, 3);
+        drop(original);
+        drop(shared);
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.curr_blocks, 1);
+    }
+
+    fn assign_reuses_buffer() {
+        let mut s = LeanString::from("the first string, longer than the inline capacity");
+        let ptr = s.as_ptr();
+        for text in [
+            "the other string, longer than the inline capacity",
+            "yet another text, longer than the inline capacity",
+        ] {
+            s.assign(text);
+            assert_eq!(s, text);
+            assert_eq!(s.as_ptr(), ptr);
+        }
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.total_blocks, 1);
+    }
+
+    fn replace_allocates_once() {
+        let haystack = LeanString::from("a-b-c-d-".repeat(100).as_str());
+        let expected_grown = "a-b-c-d-".repeat(100).replace("-", "<->");
+        let expected_shrunk = "abcd".repeat(100);
+        let stats = dhat::HeapStats::get();
+        let before = stats.total_blocks;
+
+        // growing
+        let grown = haystack.replace_lean("-", "<->");
+        assert_eq!(grown, expected_grown);
+        assert_eq!(grown.capacity(), grown.len());
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.total_blocks, before + 1);
+
+        // shrinking
+        let shrunk = grown.replace_lean("<->", "");
+        assert_eq!(shrunk, expected_shrunk);
+        assert_eq!(shrunk.capacity(), shrunk.len());
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.total_blocks, before + 2);
+    }
+
+    fn reserve_pow2_reallocates_less_than_amortized_growth() {
+        // Instead of a benchmark, compare the number of (re)allocations to grow to 10 KB.
+        let chunk = "0123456789";
+
+        let mut amortized = LeanString::new();
+        for _ in 0..1000 {
+            amortized.push_str(chunk);
+        }
+        let stats = dhat::HeapStats::get();
+        let amortized_blocks = stats.total_blocks;
+
+        let mut pow2 = LeanString::new();
+        for _ in 0..1000 {
+            pow2.reserve_pow2(chunk.len());
+            pow2.push_str(chunk);
+        }
+        assert_eq!(pow2, amortized);
+        assert_eq!(pow2.capacity(), 16384);
+        let stats = dhat::HeapStats::get();
+        let pow2_blocks = stats.total_blocks - amortized_blocks;
+
+        // 17 and 10 blocks on 64-bit architecture
+        dhat::assert!(pow2_blocks < amortized_blocks);
+    }
+
+    fn from_utf16_short_does_not_allocate() {
+        let inline_limit = size_of::<LeanString>();
+
+        // 5 chars of 3 bytes, fits inline
+        let utf16: Vec<u16> = "こんにちは".encode_utf16().collect();
+        let expected = String::from_utf16(&utf16).unwrap();
+        let stats = dhat::HeapStats::get();
+        let before = stats.total_blocks;
+        let s = LeanString::from_utf16(&utf16).unwrap();
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.total_blocks - before, (15 > inline_limit) as u64);
+        assert_eq!(s, expected);
+
+        // a few code units, but longer than inline in bytes, is allocated once
+        let utf16: Vec<u16> = "🦀こんにちは世界".encode_utf16().collect();
+        let stats = dhat::HeapStats::get();
+        let before = stats.total_blocks;
+        let s = LeanString::from_utf16(&utf16).unwrap();
+        let stats = dhat::HeapStats::get();
+        dhat::assert_eq!(stats.total_blocks - before, (s.len() > inline_limit) as u64);
+        assert_eq!(s, "🦀こんにちは世界");
+        assert_eq!(s.capacity(), s.len().max(inline_limit));
+    }
+}
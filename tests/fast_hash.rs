@@ -0,0 +1,66 @@
+#![cfg(feature = "fast_hash")]
+
+use lean_string::LeanString;
+use std::collections::HashSet;
+
+#[test]
+fn same_for_every_representation() {
+    let text: &'static str = "0123456789abcdefghijklmnopqrstuvwxyz";
+    for len in 0..=text.len() {
+        let heap = LeanString::from(&text[..len]);
+        let static_ = LeanString::from_static_str(&text[..len]);
+        let mut built = LeanString::with_capacity(100);
+        built.push_str(&text[..len]);
+        assert_eq!(heap.fast_hash(), static_.fast_hash());
+        assert_eq!(heap.fast_hash(), built.fast_hash());
+    }
+}
+
+#[test]
+fn distinguishes_length_and_padding() {
+    // trailing NULs are not lost in the last partial word
+    let hashes: HashSet<u64> = ["", "\0", "\0\0", "a", "a\0", "abcdefgh", "abcdefgh\0"]
+        .map(|s| LeanString::from(s).fast_hash())
+        .into();
+    assert_eq!(hashes.len(), 7);
+}
+
+#[test]
+fn every_byte_of_the_tail_counts() {
+    // a partial last word of every length, changing one byte at a time
+    for len in 9..16 {
+        let base = "x".repeat(len);
+        let mut hashes = HashSet::from([LeanString::from(base.as_str()).fast_hash()]);
+        for pos in 0..len {
+            let mut key = base.clone().into_bytes();
+            key[pos] = b'y';
+            hashes.insert(LeanString::from_utf8(&key).unwrap().fast_hash());
+        }
+        assert_eq!(hashes.len(), len + 1, "len: {len}");
+    }
+}
+
+#[test]
+fn distribution() {
+    const KEYS: usize = 1 << 14;
+    const BUCKETS: usize = 64;
+
+    // 8-byte keys which differ only in a few bits
+    let hashes: Vec<u64> =
+        (0..KEYS as u64).map(|i| LeanString::from(format!("key{i:05}")).fast_hash()).collect();
+
+    let unique: HashSet<u64> = hashes.iter().copied().collect();
+    assert_eq!(unique.len(), KEYS);
+
+    // both the low bits (used by power-of-two tables) and the high bits are spread evenly
+    for shift in [0, 58] {
+        let mut counts = [0usize; BUCKETS];
+        for hash in &hashes {
+            counts[(hash >> shift) as usize % BUCKETS] += 1;
+        }
+        let expected = KEYS / BUCKETS;
+        for count in counts {
+            assert!(count > expected / 2 && count < expected * 2, "{counts:?}");
+        }
+    }
+}
@@ -23,7 +23,7 @@ fn new_from_char() {
 
 #[test]
 fn from_around_inline_limit() {
-    let s = &String::from("0123456789abcdefg");
+    let s = &String::from("0123456789abcdefghijklmnopqrstuvwxyz");
 
     let inline = LeanString::from(&s[..INLINE_LIMIT - 1]);
     assert_eq!(inline, s[..INLINE_LIMIT - 1]);
@@ -43,7 +43,7 @@ fn from_around_inline_limit() {
 
 #[test]
 fn from_around_inline_limit_static() {
-    let s: &'static str = "0123456789abcdefg";
+    let s: &'static str = "0123456789abcdefghijklmnopqrstuvwxyz";
 
     let inline = LeanString::from_static_str(&s[..INLINE_LIMIT - 1]);
     assert_eq!(inline, s[..INLINE_LIMIT - 1]);
@@ -92,7 +92,7 @@ fn push_cow() {
 
     // s into heap
     s.push_str("90");
-    assert!(s.is_heap_allocated());
+    assert_eq!(s.is_heap_allocated(), INLINE_LIMIT < 18);
     assert_eq!(s.len(), 18);
 
     // clone and push
@@ -119,9 +119,9 @@ fn push_to_static() {
     inline.push_str("12345678");
     assert_eq!(inline, "abcdefgh12345678");
     assert_eq!(inline.len(), 16);
-    if cfg!(target_pointer_width = "64") {
+    if INLINE_LIMIT >= 16 {
         assert!(!inline.is_heap_allocated());
-        assert_eq!(inline.capacity(), 16);
+        assert_eq!(inline.capacity(), INLINE_LIMIT);
     } else {
         assert!(inline.capacity() >= 16);
     }
@@ -129,7 +129,7 @@ fn push_to_static() {
     inline.push_str("90");
     assert_eq!(inline, "abcdefgh1234567890");
     assert_eq!(inline.len(), 18);
-    assert!(inline.is_heap_allocated());
+    assert_eq!(inline.is_heap_allocated(), INLINE_LIMIT < 18);
 
     let mut static_ = LeanString::from_static_str("abcdefghijklmnopqrstuvwxyz");
     assert_eq!(static_, "abcdefghijklmnopqrstuvwxyz");
@@ -156,7 +156,9 @@ fn pop_keep_capacity() {
     assert_eq!(inline.pop(), Some('H'));
     assert_eq!(inline, "");
     assert!(inline.is_empty());
-    assert_eq!(inline.capacity(), INLINE_LIMIT);
+    if cfg!(target_pointer_width = "64") {
+        assert_eq!(inline.capacity(), INLINE_LIMIT);
+    }
 
     let mut heap = LeanString::from("abcdefghijklmnopqrstuvwxyz");
     assert_eq!(heap.pop(), Some('z'));
@@ -218,16 +220,16 @@ fn pop_from_static() {
 
 #[test]
 fn pop_from_static_cow() {
-    let mut static1 = LeanString::from_static_str("0123456789abcdef!");
+    let mut static1 = LeanString::from_static_str("0123456789abcdefghijklmnopqrstuvwxyz!");
     assert_eq!(static1.pop(), Some('!'));
     let static2 = static1.clone();
-    assert_eq!(static1.pop(), Some('f'));
+    assert_eq!(static1.pop(), Some('z'));
 
-    assert_eq!(static1, "0123456789abcde");
+    assert_eq!(static1, "0123456789abcdefghijklmnopqrstuvwxy");
     assert_eq!(static1.capacity(), static1.len());
     assert!(!static1.is_heap_allocated());
 
-    assert_eq!(static2, "0123456789abcdef");
+    assert_eq!(static2, "0123456789abcdefghijklmnopqrstuvwxyz");
     assert_eq!(static2.capacity(), static2.len());
     assert!(!static2.is_heap_allocated());
 
@@ -349,12 +351,12 @@ fn insert() {
 
 #[test]
 fn insert_to_static() {
-    let mut static_ = LeanString::from_static_str("01234567890123456789");
+    let mut static_ = LeanString::from_static_str("012345678901234567890123456789");
     let cloned = static_.clone();
     static_.insert(10, 'a');
-    assert_eq!(static_, "0123456789a0123456789");
+    assert_eq!(static_, "0123456789a01234567890123456789");
     assert!(static_.is_heap_allocated());
-    assert_eq!(cloned, "01234567890123456789");
+    assert_eq!(cloned, "012345678901234567890123456789");
     assert!(!cloned.is_heap_allocated());
 }
 
@@ -367,10 +369,10 @@ fn insert_fail() {
 
 #[test]
 fn convert_static_to_inline_with_reserve() {
-    let s: &'static str = "1234567890ABCDEFGHIJ";
+    let s: &'static str = "1234567890ABCDEFGHIJKLMNOPQRSTUVWXYZ";
     let mut static_ = LeanString::from_static_str(s);
 
-    for _ in 0..10 {
+    for _ in 0..26 {
         static_.pop();
     }
 
@@ -378,7 +380,11 @@ fn convert_static_to_inline_with_reserve() {
     assert_eq!(static_.capacity(), static_.len()); // still in static buffer
 
     static_.reserve(1);
-    assert_eq!(static_.capacity(), INLINE_LIMIT);
+    if static_.len() < INLINE_LIMIT {
+        assert_eq!(static_.capacity(), INLINE_LIMIT);
+    } else {
+        assert!(static_.is_heap_allocated());
+    }
 }
 
 #[test]
@@ -387,7 +393,7 @@ fn clear_cow() {
     inline.clear();
     assert_eq!(inline, "");
 
-    let mut heap: LeanString = core::iter::repeat('a').take(100).collect();
+    let mut heap: LeanString = core::iter::repeat_n('a', 100).collect();
     let cloned = heap.clone();
     heap.clear();
 
@@ -405,3 +411,2160 @@ fn extend_char() {
     s.extend("world!".chars());
     assert_eq!(s, "Hello, world!");
 }
+
+#[test]
+fn small_string_alias() {
+    use lean_string::SmallString;
+
+    let mut s = SmallString::from("Hello");
+    s.push_str(", world!");
+    assert_eq!(s, "Hello, world!");
+
+    let lean: LeanString = s;
+    assert_eq!(lean, "Hello, world!");
+}
+
+#[test]
+fn with_capacity_for_exact_fit() {
+    let pieces = ["0123456789", "abcdefghij", "👍👍", ""];
+    let mut s = LeanString::with_capacity_for(&pieces).unwrap();
+    assert!(s.is_empty());
+    assert!(s.is_heap_allocated());
+    assert_eq!(s.capacity(), 28);
+
+    let ptr = s.as_ptr();
+    for piece in pieces {
+        s.push_str(piece);
+        assert_eq!(s.as_ptr(), ptr);
+    }
+    assert_eq!(s, "0123456789abcdefghij👍👍");
+    assert_eq!(s.len(), s.capacity());
+
+    let inline = LeanString::with_capacity_for(&["abc", "def"]).unwrap();
+    assert!(!inline.is_heap_allocated());
+    assert_eq!(inline.capacity(), INLINE_LIMIT);
+
+    let empty = LeanString::with_capacity_for(&[]).unwrap();
+    assert_eq!(empty.capacity(), INLINE_LIMIT);
+}
+
+#[test]
+fn fill_spare_capacity() {
+    let mut inline = LeanString::from("abc");
+    let spare = inline.spare_capacity_mut().unwrap();
+    assert_eq!(spare.len(), INLINE_LIMIT - 3);
+    spare[0].write(b'd');
+    spare[1].write(b'e');
+    unsafe { inline.set_len(5) };
+    assert_eq!(inline, "abcde");
+
+    let mut heap = LeanString::from("abcdefghijklmnopqrstuvwxyz");
+    let cloned = heap.clone();
+    heap.reserve(4);
+    let spare = heap.spare_capacity_mut().unwrap();
+    assert!(spare.len() >= 4);
+    for (dst, src) in spare.iter_mut().zip("🦀".as_bytes()) {
+        dst.write(*src);
+    }
+    unsafe { heap.set_len(30) };
+    assert_eq!(heap, "abcdefghijklmnopqrstuvwxyz🦀");
+    assert_eq!(cloned, "abcdefghijklmnopqrstuvwxyz");
+
+    // shared buffer is cloned before handing out the spare capacity
+    let mut shared = LeanString::from("0123456789abcdefghijklmnopqrstuvwxyz");
+    let cloned = shared.clone();
+    let spare = shared.spare_capacity_mut().unwrap();
+    assert!(spare.is_empty());
+    assert_ne!(shared.as_ptr(), cloned.as_ptr());
+
+    let mut static_ = LeanString::from_static_str("0123456789abcdefghijklmnopqrstuvwxyz");
+    let _ = static_.spare_capacity_mut().unwrap();
+    assert!(static_.is_heap_allocated());
+    assert_eq!(static_, "0123456789abcdefghijklmnopqrstuvwxyz");
+}
+
+#[test]
+#[cfg(target_pointer_width = "32")]
+fn heap_capacity_limit_32bit() {
+    // 2^24 - 1 is reserved as a tag on 32-bit architecture.
+    const MAX: usize = (1 << 24) - 2;
+
+    let s = LeanString::try_with_capacity(MAX).unwrap();
+    assert_eq!(s.capacity(), MAX);
+    assert!(LeanString::try_with_capacity(MAX + 1).is_err());
+
+    let mut s = LeanString::from("abc");
+    assert!(s.try_reserve(MAX - 2).is_err());
+    assert_eq!(s, "abc");
+    s.try_reserve(MAX - 3).unwrap();
+    assert_eq!(s.capacity(), MAX);
+
+    // amortized growth is capped instead of failing
+    let mut s = LeanString::from("a".repeat(MAX - 1));
+    s.push('b');
+    assert_eq!(s.len(), MAX);
+    assert_eq!(s.capacity(), MAX);
+    assert!(s.try_push('c').is_err());
+    assert_eq!(s.len(), MAX);
+}
+
+#[test]
+fn from_str_truncated() {
+    // '🦀' is 4 bytes
+    let text = "ab🦀cd";
+    assert_eq!(LeanString::from_str_truncated(text, 0), "");
+    assert_eq!(LeanString::from_str_truncated(text, 2), "ab");
+    assert_eq!(LeanString::from_str_truncated(text, 3), "ab");
+    assert_eq!(LeanString::from_str_truncated(text, 5), "ab");
+    assert_eq!(LeanString::from_str_truncated(text, 6), "ab🦀");
+    assert_eq!(LeanString::from_str_truncated(text, 8), "ab🦀cd");
+    assert_eq!(LeanString::from_str_truncated(text, usize::MAX), "ab🦀cd");
+
+    let long = "abcdefghijklmnopqrstuvwxyz";
+    let heap = LeanString::from_str_truncated(long, 20);
+    assert_eq!(heap, "abcdefghijklmnopqrst");
+    assert_eq!(heap.is_heap_allocated(), 20 > INLINE_LIMIT);
+}
+
+#[test]
+fn retain_reporting() {
+    let mut inline = LeanString::from("abc");
+    assert!(!inline.retain_reporting(|_| true));
+    assert_eq!(inline, "abc");
+    assert!(inline.retain_reporting(|c| c != 'b'));
+    assert_eq!(inline, "ac");
+
+    let mut heap = LeanString::from("こんにちは, world!");
+    let cloned = heap.clone();
+    assert!(heap.retain_reporting(|c| c.is_ascii()));
+    assert_eq!(heap, ", world!");
+    assert_eq!(cloned, "こんにちは, world!");
+
+    let mut empty = LeanString::new();
+    assert!(!empty.retain_reporting(|_| false));
+
+    let mut all = LeanString::from("🦀🦀🦀🦀🦀");
+    assert!(all.retain_reporting(|_| false));
+    assert!(all.is_empty());
+}
+
+#[test]
+fn insert_char_repeated() {
+    let mut s = LeanString::from("abc");
+    s.insert_char_repeated(0, '-', 2);
+    assert_eq!(s, "--abc");
+    s.insert_char_repeated(3, 'é', 3);
+    assert_eq!(s, "--aééébc");
+    let len = s.len();
+    s.insert_char_repeated(len, '🦀', 2);
+    assert_eq!(s, "--aééébc🦀🦀");
+
+    let mut s = LeanString::from_static_str("static");
+    s.insert_char_repeated(3, ' ', 0);
+    assert_eq!(s, "static");
+    s.insert_char_repeated(3, ' ', INLINE_LIMIT);
+    assert_eq!(s.len(), 6 + INLINE_LIMIT);
+    assert_eq!(s.trim_matches(char::is_alphabetic).len(), INLINE_LIMIT);
+
+    let mut s = LeanString::new();
+    assert!(s.try_insert_char_repeated(0, 'a', usize::MAX).is_err());
+    assert!(s.is_empty());
+}
+
+#[test]
+#[should_panic(expected = "index is not a char boundary or out of bounds (index: 1)")]
+fn insert_char_repeated_not_char_boundary() {
+    let mut s = LeanString::from("あ");
+    s.insert_char_repeated(1, 'a', 1);
+}
+
+#[test]
+fn into_chars_meet_in_the_middle() {
+    for text in ["", "a", "ab", "abc", "aé🦀あz", "こんにちは, world! 🦀🦀🦀"] {
+        let expected: Vec<char> = text.chars().collect();
+
+        let mut chars = LeanString::from(text).into_chars();
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+        while let Some(c) = chars.next() {
+            front.push(c);
+            match chars.next_back() {
+                Some(c) => back.push(c),
+                None => break,
+            }
+        }
+        assert_eq!(chars.next(), None);
+        assert_eq!(chars.next_back(), None);
+        assert_eq!(chars.as_str(), "");
+
+        back.reverse();
+        front.extend(back);
+        assert_eq!(front, expected);
+    }
+}
+
+#[test]
+fn into_chars_rev_and_size_hint() {
+    let s = LeanString::from("aé🦀あz");
+    let shared = s.clone();
+
+    let chars = s.into_chars();
+    assert_eq!(chars.size_hint(), "aé🦀あz".chars().size_hint());
+    assert_eq!(chars.clone().count(), 5);
+    assert_eq!(chars.clone().last(), Some('z'));
+    assert_eq!(chars.rev().collect::<String>(), "zあ🦀éa");
+    assert_eq!(shared, "aé🦀あz");
+}
+
+#[test]
+fn into_char_indices_matches_char_indices() {
+    for text in ["", "a", "aé🦀あz", "こんにちは, world! 🦀🦀🦀"] {
+        let expected: Vec<(usize, char)> = text.char_indices().collect();
+
+        let forward: Vec<_> = LeanString::from(text).into_char_indices().collect();
+        assert_eq!(forward, expected);
+
+        let mut backward: Vec<_> = LeanString::from(text).into_char_indices().rev().collect();
+        backward.reverse();
+        assert_eq!(backward, expected);
+    }
+
+    // meeting in the middle keeps the offsets of the original string
+    let mut indices = LeanString::from("aé🦀あz").into_char_indices();
+    assert_eq!(indices.next(), Some((0, 'a')));
+    assert_eq!(indices.next_back(), Some((10, 'z')));
+    assert_eq!(indices.next(), Some((1, 'é')));
+    assert_eq!(indices.next_back(), Some((7, 'あ')));
+    assert_eq!(indices.as_str(), "🦀");
+    assert_eq!(indices.clone().count(), 1);
+    assert_eq!(indices.next(), Some((3, '🦀')));
+    assert_eq!(indices.next(), None);
+    assert_eq!(indices.next_back(), None);
+}
+
+#[test]
+fn into_bytes_iter_consumes_fully() {
+    for text in ["", "a", "aé🦀あz", "a heap allocated string, longer than inline 🦀"] {
+        let bytes = LeanString::from(text).into_bytes_iter();
+        assert_eq!(bytes.len(), text.len());
+        assert_eq!(bytes.clone().collect::<Vec<u8>>(), text.as_bytes());
+        assert_eq!(bytes.rev().collect::<Vec<u8>>(), text.bytes().rev().collect::<Vec<u8>>());
+    }
+
+    let mut bytes = LeanString::from("abc").into_bytes_iter();
+    assert_eq!(bytes.next(), Some(b'a'));
+    assert_eq!(bytes.next_back(), Some(b'c'));
+    assert_eq!(bytes.as_slice(), b"b");
+    assert_eq!(bytes.next(), Some(b'b'));
+    assert_eq!(bytes.next(), None);
+    assert_eq!(bytes.next_back(), None);
+    assert_eq!(bytes.as_slice(), b"");
+}
+
+#[test]
+fn into_bytes_iter_dropped_early() {
+    let s = LeanString::from("a heap allocated string, longer than inline");
+    let shared = s.clone();
+
+    let mut bytes = s.into_bytes_iter();
+    assert_eq!(bytes.next(), Some(b'a'));
+    assert_eq!(bytes.next_back(), Some(b'e'));
+    drop(bytes);
+    assert_eq!(shared, "a heap allocated string, longer than inline");
+
+    let mut bytes = LeanString::from_static_str("static").into_bytes_iter();
+    assert_eq!(bytes.next(), Some(b's'));
+    drop(bytes);
+}
+
+#[test]
+fn push_cow_borrowed_and_owned() {
+    use std::borrow::Cow;
+
+    let long = "a heap allocated string, longer than inline";
+
+    let mut s = LeanString::new();
+    s.push_cow(Cow::Borrowed("foo"));
+    s.push_cow(Cow::Owned(String::from("bar")));
+    assert_eq!(s, "foobar");
+
+    let mut s = LeanString::new();
+    s.push_cow(Cow::Owned(String::from(long)));
+    assert_eq!(s, long);
+    s.push_cow(Cow::Owned(String::from("!")));
+    assert_eq!(s, format!("{long}!"));
+
+    // the reserved capacity of an empty string is kept
+    let mut s = LeanString::with_capacity(100);
+    s.push_cow(Cow::Owned(String::from(long)));
+    assert_eq!(s, long);
+    assert_eq!(s.capacity(), 100);
+}
+
+#[test]
+fn from_str_with_headroom() {
+    let long = "a heap allocated string, longer than inline";
+    for (text, extra) in [("", 0), ("abc", 0), ("abc", 1), ("abc", 100), (long, 0), (long, 10)] {
+        let s = LeanString::from_str_with_headroom(text, extra);
+        assert_eq!(s, text);
+        assert!(s.capacity() >= text.len() + extra);
+        assert!(s.capacity() >= INLINE_LIMIT);
+    }
+
+    let mut s = LeanString::from_str_with_headroom(long, 10);
+    assert_eq!(s.capacity(), long.len() + 10);
+    s.push_str("0123456789");
+    assert_eq!(s.capacity(), long.len() + 10);
+
+    assert!(LeanString::try_from_str_with_headroom("abc", usize::MAX).is_err());
+    assert!(LeanString::try_from_str_with_headroom("abc", usize::MAX - 3).is_err());
+}
+
+#[test]
+fn reverse_by_char() {
+    for text in [
+        "",
+        "a",
+        "ab",
+        "🦀",
+        "aé🦀あz",
+        "こんにちは, world! 🦀🦀🦀",
+        "a heap allocated string, longer than inline ñ",
+    ] {
+        let expected: String = text.chars().rev().collect();
+
+        let mut s = LeanString::from(text);
+        s.reverse();
+        assert_eq!(s, expected);
+        s.reverse();
+        assert_eq!(s, text);
+    }
+
+    // a shared buffer is cloned, a static buffer is copied
+    let s = LeanString::from("a heap allocated string, longer than inline🦀");
+    let mut reversed = s.clone();
+    reversed.reverse();
+    assert_eq!(reversed, "🦀enilni naht regnol ,gnirts detacolla paeh a");
+    assert_eq!(s, "a heap allocated string, longer than inline🦀");
+
+    let mut s = LeanString::from_static_str("static ñ string");
+    s.reverse();
+    assert_eq!(s, "gnirts ñ citats");
+}
+
+#[test]
+fn replace_char_same_len_in_place() {
+    let mut s = LeanString::from("a/heap/allocated/string/longer/than/inline/");
+    let capacity = s.capacity();
+    s.replace_char('/', '-');
+    assert_eq!(s, "a-heap-allocated-string-longer-than-inline-");
+    assert_eq!(s.capacity(), capacity);
+
+    let mut s = LeanString::from("éaéaé");
+    s.replace_char('é', 'ñ');
+    assert_eq!(s, "ñañañ");
+
+    let mut s = LeanString::from("🦀あ🦀あ");
+    s.replace_char('🦀', '🐍');
+    assert_eq!(s, "🐍あ🐍あ");
+
+    // no occurrence, or the same char, keeps a shared buffer
+    let s = LeanString::from("a heap allocated string, longer than inline");
+    let mut shared = s.clone();
+    shared.replace_char('/', '-');
+    shared.replace_char('a', 'a');
+    assert_eq!(shared.as_ptr(), s.as_ptr());
+
+    // a shared buffer is cloned before modification
+    shared.replace_char('a', 'A');
+    assert_eq!(shared, "A heAp AllocAted string, longer thAn inline");
+    assert_eq!(s, "a heap allocated string, longer than inline");
+
+    let mut s = LeanString::from_static_str("static/str");
+    s.replace_char('/', '_');
+    assert_eq!(s, "static_str");
+}
+
+#[test]
+fn replace_char_different_len() {
+    let mut s = LeanString::from("a-b-c");
+    s.replace_char('-', '→');
+    assert_eq!(s, "a→b→c");
+    s.replace_char('→', '/');
+    assert_eq!(s, "a/b/c");
+    s.replace_char('/', '🦀');
+    assert_eq!(s, "a🦀b🦀c");
+    s.replace_char('x', '🦀');
+    assert_eq!(s, "a🦀b🦀c");
+
+    let mut s = LeanString::from("a heap allocated string, longer than inline");
+    s.replace_char(' ', 'あ');
+    assert_eq!(s, "aあheapあallocatedあstring,あlongerあthanあinline");
+}
+
+#[test]
+fn ascii_case_insensitive_key() {
+    use lean_string::AsciiCaseInsensitive;
+    use std::{
+        collections::{hash_map::RandomState, BTreeMap, HashMap},
+        hash::BuildHasher,
+    };
+
+    let key = |s: &str| AsciiCaseInsensitive(LeanString::from(s));
+
+    let mut map = HashMap::new();
+    map.insert(key("Foo"), 1);
+    assert_eq!(map.get(&key("foo")), Some(&1));
+    assert_eq!(map.get(&key("FOO")), Some(&1));
+    assert_eq!(map.get(&key("fo")), None);
+    assert_eq!(map.insert(key("fOO"), 2), Some(1));
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.keys().next().unwrap().as_str(), "Foo");
+
+    let mut map = BTreeMap::new();
+    map.insert(key("Foo"), 1);
+    map.insert(key("bar"), 2);
+    assert_eq!(map.get(&key("BAR")), Some(&2));
+    assert_eq!(map.keys().map(|k| k.as_str()).collect::<Vec<_>>(), ["bar", "Foo"]);
+
+    // longer than the buffer used for hashing, and non-ASCII chars are compared as they are
+    let long = "A Heap Allocated String, Longer Than The Inline Capacity And The Hashing Buffer";
+    let state = RandomState::new();
+    assert_eq!(state.hash_one(key(long)), state.hash_one(key(&long.to_ascii_uppercase())));
+    assert_eq!(key(long), key(&long.to_ascii_lowercase()));
+    assert_ne!(key("É"), key("é"));
+}
+
+#[test]
+fn split_off_and_take_suffix_from() {
+    let long = "a heap allocated string, longer than inline ñ";
+    for text in ["", "abc", "aé🦀あz", long] {
+        let boundaries = (0..=text.len()).filter(|&i| text.is_char_boundary(i));
+        for at in boundaries {
+            let mut s = LeanString::from(text);
+            let tail = s.split_off(at);
+            assert_eq!(s, text[..at]);
+            assert_eq!(tail, text[at..]);
+
+            let mut s = LeanString::from(text);
+            assert_eq!(s.take_suffix_from(at), text[at..]);
+            assert_eq!(s, text[..at]);
+        }
+    }
+
+    // at 0, mid and len
+    let mut s = LeanString::from(long);
+    let capacity = s.capacity();
+    assert_eq!(s.take_suffix_from(s.len()), "");
+    assert_eq!(s.take_suffix_from(25), "longer than inline ñ");
+    assert_eq!(s.take_suffix_from(0), "a heap allocated string, ");
+    assert_eq!(s, "");
+    assert_eq!(s.capacity(), capacity);
+
+    // a shared buffer is kept, a static tail is not copied
+    let s = LeanString::from(long);
+    let mut shared = s.clone();
+    assert_eq!(shared.split_off(2), &long[2..]);
+    assert_eq!(shared, "a ");
+    assert_eq!(s, long);
+
+    let mut s = LeanString::from_static_str(long);
+    let tail = s.split_off(2);
+    assert_eq!(tail.as_ptr(), long[2..].as_ptr());
+    assert_eq!(s, "a ");
+}
+
+#[test]
+#[should_panic(expected = "index is not a char boundary or out of bounds (index: 1)")]
+fn split_off_not_char_boundary() {
+    let mut s = LeanString::from("あ");
+    s.split_off(1);
+}
+
+#[test]
+#[should_panic(expected = "index is not a char boundary or out of bounds (index: 4)")]
+fn take_suffix_from_out_of_bounds() {
+    let mut s = LeanString::from("abc");
+    s.take_suffix_from(4);
+}
+
+#[test]
+fn from_iter_dedup() {
+    assert_eq!(LeanString::from_iter_dedup([]), "");
+    assert_eq!(LeanString::from_iter_dedup(["a"]), "a");
+    assert_eq!(LeanString::from_iter_dedup(["a", "a", "a"]), "a");
+
+    // consecutive duplicates are collapsed
+    let s = LeanString::from_iter_dedup(["usr", "/", "/", "/", "local", "/", "bin"]);
+    assert_eq!(s, "usr/local/bin");
+
+    // non-adjacent duplicates are kept
+    assert_eq!(LeanString::from_iter_dedup(["ab", "c", "ab", "c"]), "abcabc");
+
+    // pieces are compared as a whole, not by content of the result
+    assert_eq!(LeanString::from_iter_dedup(["a", "", "a", "aa"]), "aaaa");
+    assert_eq!(LeanString::from_iter_dedup(["", ""]), "");
+}
+
+#[test]
+fn try_as_unique_mut() {
+    let long = "a heap allocated string, longer than inline";
+
+    let mut inline = LeanString::from("inline");
+    inline.try_as_unique_mut().unwrap().make_ascii_uppercase();
+    assert_eq!(inline, "INLINE");
+
+    let mut unique = LeanString::from(long);
+    let ptr = unique.as_ptr();
+    assert_eq!(unique.try_as_unique_mut(), Some(&mut String::from(long)[..]));
+    assert_eq!(unique.as_ptr(), ptr);
+
+    // shared and static buffers are not copied
+    let shared = unique.clone();
+    assert_eq!(unique.try_as_unique_mut(), None);
+    assert_eq!(unique.as_ptr(), shared.as_ptr());
+    drop(shared);
+    assert!(unique.try_as_unique_mut().is_some());
+
+    let mut static_ = LeanString::from_static_str(long);
+    assert_eq!(static_.try_as_unique_mut(), None);
+    assert_eq!(static_.as_ptr(), long.as_ptr());
+}
+
+#[test]
+fn push_int_and_uint() {
+    for value in [0, 1, -1, 9, -10, 12345, i64::MAX, i64::MIN] {
+        let mut s = LeanString::from("v=");
+        s.push_int(value);
+        assert_eq!(s, format!("v={value}"));
+    }
+    for value in [0, 1, 10, 99999, u64::MAX] {
+        let mut s = LeanString::from("v=");
+        s.push_uint(value);
+        assert_eq!(s, format!("v={value}"));
+    }
+
+    // crossing the inline limit
+    let mut s = LeanString::new();
+    for value in -3..3 {
+        s.push_int(value * 1_000_000_007);
+        s.push(',');
+    }
+    assert_eq!(s, "-3000000021,-2000000014,-1000000007,0,1000000007,2000000014,");
+}
+
+#[test]
+fn push_f64_with_precision() {
+    let values = [
+        0.0,
+        -0.0,
+        1.0,
+        -1.5,
+        0.5,
+        0.125,
+        0.375,
+        1.005,
+        2.5,
+        -0.001,
+        123456.789,
+        1e-7,
+        1e300,
+        f64::MAX,
+        f64::MIN_POSITIVE,
+        5e-324,
+    ];
+    for value in values {
+        for precision in [0, 1, 2, 3, 6, 17, 30, 400, 1074, 1100] {
+            let mut s = LeanString::from("v=");
+            s.push_f64(value, precision);
+            assert_eq!(s, format!("v={value:.precision$}"));
+        }
+    }
+
+    for (value, expected) in
+        [(f64::NAN, "NaN"), (f64::INFINITY, "inf"), (f64::NEG_INFINITY, "-inf")]
+    {
+        for precision in [0, 3] {
+            let mut s = LeanString::new();
+            s.push_f64(value, precision);
+            assert_eq!(s, expected);
+        }
+    }
+}
+
+#[test]
+fn assign() {
+    let long = "a heap allocated string, longer than inline";
+
+    // inline
+    let mut s = LeanString::from("abc");
+    s.assign("xyz");
+    assert_eq!(s, "xyz");
+    s.assign(long);
+    assert_eq!(s, long);
+
+    // unique heap buffer with enough capacity
+    let mut s = LeanString::with_capacity(100);
+    let ptr = s.as_ptr();
+    s.assign(long);
+    s.assign("");
+    s.assign(long);
+    assert_eq!(s, long);
+    assert_eq!(s.as_ptr(), ptr);
+    assert_eq!(s.capacity(), 100);
+
+    // too small
+    let mut s = LeanString::from(&long[..30]);
+    s.assign(long);
+    assert_eq!(s, long);
+
+    // shared buffer
+    let original = LeanString::from(long);
+    let mut s = original.clone();
+    s.assign("shared buffer is not modified in place");
+    assert_eq!(s, "shared buffer is not modified in place");
+    assert_eq!(original, long);
+
+    // static buffer
+    let mut s = LeanString::from_static_str(long);
+    s.assign("static buffer is not modified in place");
+    assert_eq!(s, "static buffer is not modified in place");
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "bytes are not valid UTF-8")]
+fn from_utf8_unchecked_invalid_in_debug() {
+    let _ = unsafe { LeanString::from_utf8_unchecked(b"abc\xFF") };
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "bytes are not valid UTF-8")]
+fn set_len_invalid_in_debug() {
+    let mut s = LeanString::with_capacity(100);
+    let spare = s.spare_capacity_mut().unwrap();
+    spare[0].write(b'a');
+    spare[1].write(0xE3);
+    unsafe { s.set_len(2) };
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "new_len is greater than capacity")]
+fn set_len_beyond_capacity_in_debug() {
+    let mut s = LeanString::new();
+    unsafe { s.set_len(s.capacity() + 1) };
+}
+
+#[test]
+fn lines_owned_matches_lines() {
+    for text in [
+        "",
+        "\n",
+        "\r\n",
+        "a",
+        "a\n",
+        "a\r\nb\r\n",
+        "a\n\nb",
+        "a\rb\n\r",
+        "no newline at the end, but longer than inline\r\nsecond line",
+        "trailing newlines\n\n",
+    ] {
+        let lines: Vec<LeanString> = LeanString::from(text).lines_owned().collect();
+        assert_eq!(lines, text.lines().collect::<Vec<_>>(), "{text:?}");
+    }
+}
+
+#[test]
+fn reserve_pow2() {
+    for (text, additional) in [("", 100), ("abc", 61), ("abc", 1000), ("a".repeat(300).as_str(), 1)]
+    {
+        let mut s = LeanString::from(text);
+        s.reserve_pow2(additional);
+        assert_eq!(s, text);
+        assert!(s.capacity() >= text.len() + additional);
+        assert!(s.capacity().is_power_of_two(), "{}", s.capacity());
+    }
+
+    // fits inline
+    let mut s = LeanString::from("abc");
+    s.reserve_pow2(1);
+    assert!(!s.is_heap_allocated());
+
+    // enough capacity
+    let mut s = LeanString::with_capacity(100);
+    s.reserve_pow2(100);
+    assert_eq!(s.capacity(), 100);
+    s.reserve_pow2(101);
+    assert_eq!(s.capacity(), 128);
+
+    // growing in unpredictable increments keeps a power of two
+    let mut s = LeanString::new();
+    for (i, additional) in [17, 3, 90, 1, 250, 7, 600].into_iter().enumerate() {
+        s.reserve_pow2(additional);
+        s.push_str(&"x".repeat(additional));
+        assert_eq!(s.len(), [17, 20, 110, 111, 361, 368, 968][i]);
+        assert!(s.capacity().is_power_of_two() || s.capacity() == INLINE_LIMIT);
+    }
+
+    // shared and static buffers
+    let long = "a heap allocated string, longer than inline";
+    let original = LeanString::from(long);
+    let mut shared = original.clone();
+    shared.reserve_pow2(1);
+    assert_eq!(shared.capacity(), 64);
+    assert_eq!(original.capacity(), long.len());
+
+    let mut s = LeanString::from_static_str(long);
+    s.reserve_pow2(30);
+    assert_eq!(s.capacity(), 128);
+    assert_eq!(s, long);
+
+    assert!(LeanString::new().try_reserve_pow2(usize::MAX).is_err());
+}
+
+#[test]
+fn from_ascii_array() {
+    let s = LeanString::from_ascii_array(*b"ABCD1234").unwrap();
+    assert_eq!(s, "ABCD1234");
+    if 8 <= INLINE_LIMIT {
+        assert!(!s.is_heap_allocated());
+    }
+
+    let s = LeanString::from_ascii_array([b'x'; INLINE_LIMIT]).unwrap();
+    assert_eq!(s, "x".repeat(INLINE_LIMIT));
+    assert!(!s.is_heap_allocated());
+
+    let s = LeanString::from_ascii_array([b'x'; 100]).unwrap();
+    assert_eq!(s, "x".repeat(100));
+
+    assert_eq!(LeanString::from_ascii_array([]).unwrap(), "");
+    assert_eq!(LeanString::from_ascii_array(*b"\xE3\x81\x82").unwrap(), "あ");
+
+    assert!(LeanString::from_ascii_array(*b"ABCD\xFF234").is_err());
+    assert!(LeanString::from_ascii_array([0xE3, 0x81]).is_err());
+}
+
+#[test]
+fn swap_ascii_case() {
+    let mut s = LeanString::from("Hello, World! 0123456789 @[`{ ÄöÜ ñ 🦀 áB");
+    s.swap_ascii_case();
+    assert_eq!(s, "hELLO, wORLD! 0123456789 @[`{ ÄöÜ ñ 🦀 áb");
+    s.swap_ascii_case();
+    assert_eq!(s, "Hello, World! 0123456789 @[`{ ÄöÜ ñ 🦀 áB");
+
+    // no ASCII letter keeps a shared buffer
+    let s = LeanString::from("0123456789 !@#$%^&*() ÄÖÜ あいう");
+    let mut shared = s.clone();
+    shared.swap_ascii_case();
+    assert_eq!(shared, s);
+    assert_eq!(shared.as_ptr(), s.as_ptr());
+}
+
+#[test]
+fn try_push_inline_at_boundary() {
+    // 1-byte chars
+    let mut s = LeanString::new();
+    for _ in 0..INLINE_LIMIT {
+        assert_eq!(s.try_push_inline('a'), Ok(()));
+    }
+    assert_eq!(s.try_push_inline('a'), Err('a'));
+    assert_eq!(s, "a".repeat(INLINE_LIMIT));
+    assert!(!s.is_heap_allocated());
+
+    // 4-byte chars
+    let mut s = LeanString::from("a".repeat(INLINE_LIMIT - 4).as_str());
+    assert_eq!(s.try_push_inline('🦀'), Ok(()));
+    assert_eq!(s.len(), INLINE_LIMIT);
+
+    let mut s = LeanString::from("a".repeat(INLINE_LIMIT - 3).as_str());
+    assert_eq!(s.try_push_inline('🦀'), Err('🦀'));
+    assert_eq!(s.try_push_inline('あ'), Ok(()));
+    assert_eq!(s.len(), INLINE_LIMIT);
+
+    // a heap buffer is rejected even with spare capacity
+    let mut s = LeanString::with_capacity(100);
+    assert_eq!(s.try_push_inline('a'), Err('a'));
+    assert_eq!(s, "");
+
+    // a short static buffer becomes inline
+    let mut s = LeanString::from_static_str("abc");
+    assert_eq!(s.try_push_inline('d'), Ok(()));
+    assert_eq!(s, "abcd");
+
+    let long = "a static string, longer than inline";
+    let mut s = LeanString::from_static_str(long);
+    assert_eq!(s.try_push_inline('d'), Err('d'));
+    assert_eq!(s.as_ptr(), long.as_ptr());
+}
+
+#[test]
+fn from_utf16_around_inline_limit() {
+    for text in [
+        "",
+        "a",
+        "a".repeat(INLINE_LIMIT).as_str(),
+        "a".repeat(INLINE_LIMIT + 1).as_str(),
+        "あ".repeat(INLINE_LIMIT / 3).as_str(),
+        "あ".repeat(INLINE_LIMIT).as_str(),
+        "🦀".repeat(INLINE_LIMIT / 2).as_str(),
+        "🦀".repeat(INLINE_LIMIT).as_str(),
+    ] {
+        let utf16: Vec<u16> = text.encode_utf16().collect();
+        let s = LeanString::from_utf16(&utf16).unwrap();
+        assert_eq!(s, text);
+        assert_eq!(s.is_heap_allocated(), text.len() > INLINE_LIMIT);
+
+        // an unpaired surrogate at the end
+        let mut invalid = utf16.clone();
+        invalid.push(0xD800);
+        assert!(LeanString::from_utf16(&invalid).is_err());
+    }
+}
+
+#[test]
+fn retain_while() {
+    let long = "a heap allocated string, longer than inline";
+
+    // rejects immediately
+    let mut s = LeanString::from(long);
+    let mut calls = 0;
+    s.retain_while(|_| {
+        calls += 1;
+        false
+    });
+    assert_eq!(s, "");
+    assert_eq!(calls, 1);
+
+    // never rejects
+    let mut s = LeanString::from(long);
+    let ptr = s.as_ptr();
+    s.retain_while(|_| true);
+    assert_eq!(s, long);
+    assert_eq!(s.as_ptr(), ptr);
+
+    // stops at the first rejected char, even if later chars would be kept
+    let mut s = LeanString::from("aé🦀あz, aé🦀あz");
+    s.retain_while(|ch| ch != ',');
+    assert_eq!(s, "aé🦀あz");
+    s.retain_while(|ch| !ch.is_ascii() || ch == 'a');
+    assert_eq!(s, "aé🦀あ");
+
+    // a shared buffer is kept unchanged
+    let original = LeanString::from(long);
+    let mut shared = original.clone();
+    shared.retain_while(|ch| ch != ',');
+    assert_eq!(shared, "a heap allocated string");
+    assert_eq!(original, long);
+
+    let mut s = LeanString::from_static_str(long);
+    s.retain_while(|ch| ch != ' ');
+    assert_eq!(s, "a");
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn eq_os_str_and_path() {
+    use std::{ffi::OsStr, path::Path};
+
+    let name = LeanString::from("こんにちは.txt");
+    assert_eq!(name, *OsStr::new("こんにちは.txt"));
+    assert_eq!(*OsStr::new("こんにちは.txt"), name);
+    assert_ne!(name, *OsStr::new("こんにちは.md"));
+
+    let path = LeanString::from("/usr/local/bin");
+    assert_eq!(path, *Path::new("/usr/local/bin"));
+    assert_eq!(*Path::new("/usr/local/bin"), path);
+    assert_ne!(path, *Path::new("/usr/local"));
+
+    // the string forms are compared, not the components
+    assert_ne!(LeanString::from("/usr//local/bin/"), *Path::new("/usr/local/bin"));
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+
+        // not valid UTF-8 is never equal
+        let invalid = OsStr::from_bytes(b"abc\xFF");
+        assert_ne!(LeanString::from("abc\u{FFFD}"), *invalid);
+        assert_ne!(LeanString::from("abc"), *Path::new(invalid));
+    }
+}
+
+#[test]
+fn try_into_string_and_boxed_str() {
+    for s in [
+        LeanString::new(),
+        LeanString::from("inline"),
+        LeanString::from("a heap allocated string, longer than inline"),
+        LeanString::from_static_str("a static string, also longer than inline"),
+    ] {
+        let expected = s.to_string();
+
+        let string = s.clone().try_into_string().unwrap();
+        assert_eq!(string, expected);
+        assert_eq!(string.capacity(), expected.len());
+
+        let boxed = s.try_into_boxed_str().unwrap();
+        assert_eq!(&*boxed, expected);
+    }
+}
+
+#[test]
+fn byte_key_lookup_by_bytes() {
+    use lean_string::ByteKey;
+    use std::collections::{BTreeMap, HashMap};
+
+    let keys = ["apple", "banana, but a longer key than inline", "こんにちは"];
+
+    let mut hash_map = HashMap::new();
+    let mut btree_map = BTreeMap::new();
+    for (i, key) in keys.into_iter().enumerate() {
+        hash_map.insert(ByteKey(LeanString::from(key)), i);
+        btree_map.insert(ByteKey(LeanString::from_static_str(key)), i);
+    }
+
+    for (i, key) in keys.into_iter().enumerate() {
+        assert_eq!(hash_map.get(key.as_bytes()), Some(&i));
+        assert_eq!(btree_map.get(key.as_bytes()), Some(&i));
+    }
+    assert_eq!(hash_map.get(b"cherry".as_slice()), None);
+    assert_eq!(btree_map.get(b"cherry".as_slice()), None);
+
+    // `Ord` is the same as the bytes
+    let sorted: Vec<&[u8]> = btree_map.keys().map(|key| key.as_bytes()).collect();
+    let mut expected: Vec<&[u8]> = keys.iter().map(|key| key.as_bytes()).collect();
+    expected.sort();
+    assert_eq!(sorted, expected);
+
+    let key = ByteKey::from(LeanString::from("apple"));
+    assert_eq!(format!("{key:?} {key}"), r#""apple" apple"#);
+    assert_eq!(key.into_inner(), "apple");
+}
+
+#[test]
+fn floor_and_ceil_char_boundary() {
+    // 'a' (1), 'é' (2), 'あ' (3), '🦀' (4)
+    let s = LeanString::from("aéあ🦀");
+    let boundaries = [0, 1, 3, 6, 10];
+    assert_eq!(s.len(), 10);
+
+    for index in 0..=12 {
+        let floor = s.floor_char_boundary(index);
+        let ceil = s.ceil_char_boundary(index);
+        let expected_floor = *boundaries.iter().rev().find(|&&b| b <= index).unwrap();
+        let expected_ceil = *boundaries.iter().find(|&&b| b >= index).unwrap_or(&10);
+        assert_eq!(floor, expected_floor, "floor of {index}");
+        assert_eq!(ceil, expected_ceil, "ceil of {index}");
+        assert!(s.is_char_boundary(floor));
+        assert!(s.is_char_boundary(ceil));
+    }
+
+    let empty = LeanString::new();
+    assert_eq!(empty.floor_char_boundary(0), 0);
+    assert_eq!(empty.floor_char_boundary(3), 0);
+    assert_eq!(empty.ceil_char_boundary(3), 0);
+}
+
+#[test]
+fn into_cow_static_and_owned() {
+    use std::borrow::Cow;
+
+    let text = "a static string, longer than inline";
+    let s = LeanString::from_static_str(text);
+    let cloned = s.clone();
+    match s.into_cow() {
+        Cow::Borrowed(b) => assert_eq!(b.as_ptr(), text.as_ptr()),
+        Cow::Owned(_) => panic!("static string should be borrowed"),
+    }
+
+    // modification converts the static buffer to heap
+    let mut modified = cloned;
+    modified.push('!');
+    assert!(matches!(modified.into_cow(), Cow::Owned(s) if s == format!("{text}!")));
+
+    for s in [LeanString::from("inline"), LeanString::from(text)] {
+        assert_eq!(s.as_cow(), Cow::Borrowed(s.as_str()));
+        let cow = s.clone().into_cow();
+        assert!(matches!(cow, Cow::Owned(ref o) if o == s.as_str()));
+    }
+}
+
+#[test]
+fn starts_and_ends_with_char() {
+    for text in ["a", "ab", "éあ🦀", "🦀 in the heap allocated string あ"] {
+        let s = LeanString::from(text);
+        let first = text.chars().next().unwrap();
+        let last = text.chars().next_back().unwrap();
+        assert!(s.starts_with_char(first));
+        assert!(s.ends_with_char(last));
+        assert_eq!(s.starts_with_char(last), text.starts_with(last));
+        assert_eq!(s.ends_with_char(first), text.ends_with(first));
+    }
+
+    // a prefix of the UTF-8 encoding is not a match
+    let s = LeanString::from("é");
+    assert!(!s.starts_with_char('e'));
+    assert!(!s.ends_with_char('\u{301}'));
+
+    let empty = LeanString::new();
+    assert!(!empty.starts_with_char('a'));
+    assert!(!empty.ends_with_char('\0'));
+}
+
+#[test]
+fn dedup_chars() {
+    let mut s = LeanString::from("aaabbb");
+    s.dedup_chars();
+    assert_eq!(s, "ab");
+
+    let mut s = LeanString::from("ああ🦀🦀🦀éeé  x");
+    s.dedup_chars();
+    assert_eq!(s, "あ🦀éeé x");
+
+    let mut empty = LeanString::new();
+    empty.dedup_chars();
+    assert_eq!(empty, "");
+
+    // shared buffer is cloned before deduplicating
+    let mut heap = LeanString::from("mississippi, mississippi, mississippi");
+    let cloned = heap.clone();
+    heap.dedup_chars();
+    assert_eq!(heap, "misisipi, misisipi, misisipi");
+    assert_eq!(cloned, "mississippi, mississippi, mississippi");
+    assert_ne!(heap.as_ptr(), cloned.as_ptr());
+
+    let mut static_ = LeanString::from_static_str("zzzzzzzzzzzzzzzzzzzzzzzzzzzzzz");
+    static_.dedup_chars();
+    assert_eq!(static_, "z");
+}
+
+#[test]
+fn compact_static_to_inline() {
+    let text: &'static str = "0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut static_ = LeanString::from_static_str(text);
+    for _ in 0..30 {
+        static_.pop();
+    }
+    assert_eq!(static_, "012345");
+    assert_eq!(static_.capacity(), 6);
+    assert_eq!(static_.as_ptr(), text.as_ptr());
+
+    static_.compact();
+    assert_eq!(static_, "012345");
+    assert_eq!(static_.capacity(), INLINE_LIMIT);
+    assert_ne!(static_.as_ptr(), text.as_ptr());
+    assert!(!static_.is_heap_allocated());
+
+    // too long to be inline, stays static
+    let mut long = LeanString::from_static_str(text);
+    long.compact();
+    assert_eq!(long.capacity(), text.len());
+    assert_eq!(long.as_ptr(), text.as_ptr());
+
+    // heap and inline buffers are not changed
+    let mut heap = LeanString::from(text);
+    heap.pop();
+    let ptr = heap.as_ptr();
+    heap.compact();
+    assert_eq!(heap.as_ptr(), ptr);
+    assert!(heap.is_heap_allocated());
+
+    let mut inline = LeanString::from("abc");
+    inline.compact();
+    assert_eq!(inline, "abc");
+}
+
+#[test]
+fn replace_large_haystack() {
+    let haystack = "lorem ipsum dolor sit amet, ".repeat(1000);
+    let lean = LeanString::from(haystack.as_str());
+
+    assert_eq!(lean.count_matches("dolor"), 1000);
+    assert!(lean.contains_substr("amet, lorem"));
+    assert!(!lean.contains_substr("amet,lorem"));
+    assert_eq!(lean.replace_lean("ipsum", "🦀"), haystack.replace("ipsum", "🦀"));
+    assert_eq!(lean.replace_lean(" ", ""), haystack.replace(" ", ""));
+    assert_eq!(lean.replace_lean("", "|"), haystack.replace("", "|"));
+
+    // `str::replace` is still reachable through `Deref`
+    let replaced: String = lean.replace('i', "I");
+    assert_eq!(replaced, haystack.replace('i', "I"));
+    let replaced: String = lean.replacen('i', "I", 2);
+    assert_eq!(replaced, haystack.replacen('i', "I", 2));
+}
+
+#[test]
+fn from_char_array_and_slice() {
+    let s = LeanString::from(['a', 'b', 'c']);
+    assert_eq!(s, "abc");
+    assert!(!s.is_heap_allocated());
+
+    // 4 chars of 4 bytes fit in the inline buffer exactly on 64-bit architecture.
+    let s = LeanString::from(['🦀'; 4]);
+    assert_eq!(s, "🦀🦀🦀🦀");
+    assert_eq!(s.is_heap_allocated(), INLINE_LIMIT < 16);
+
+    let chars: Vec<char> = "こんにちは, world! Hello".chars().collect();
+    let s = LeanString::from(chars.as_slice());
+    assert_eq!(s, "こんにちは, world! Hello");
+    assert!(s.is_heap_allocated());
+    assert_eq!(s.capacity(), s.len());
+
+    assert_eq!(LeanString::from([]), "");
+    assert_eq!(LeanString::from(&[][..] as &[char]), "");
+}
+
+#[test]
+fn into_raw_from_raw_round_trip() {
+    assert_eq!(size_of::<lean_string::RawParts>(), size_of::<LeanString>());
+
+    let heap = LeanString::from("a heap allocated string, longer than inline");
+    let shared = heap.clone();
+    for s in [
+        LeanString::new(),
+        LeanString::from("inline"),
+        heap,
+        LeanString::from_static_str("a static string, also longer than inline"),
+    ] {
+        let expected = s.to_string();
+        let ptr = s.as_ptr();
+        let is_heap = s.is_heap_allocated();
+
+        let parts = s.into_raw();
+        let mut s = unsafe { LeanString::from_raw(parts) };
+        assert_eq!(s, expected);
+        assert_eq!(s.is_heap_allocated(), is_heap);
+        if s.len() > INLINE_LIMIT {
+            assert_eq!(s.as_ptr(), ptr);
+        }
+
+        // still works as a LeanString, and clone-on-write keeps the other one intact
+        s.push('!');
+        assert_eq!(s, format!("{expected}!"));
+    }
+    assert_eq!(shared, "a heap allocated string, longer than inline");
+}
+
+#[test]
+fn remaining_capacity() {
+    let mut inline = LeanString::from("abc");
+    assert_eq!(inline.remaining_capacity(), INLINE_LIMIT - 3);
+    inline.push('d');
+    assert_eq!(inline.remaining_capacity(), INLINE_LIMIT - 4);
+
+    let mut heap = LeanString::with_capacity(100);
+    heap.push_str("hello");
+    assert_eq!(heap.remaining_capacity(), 95);
+
+    let static_ = LeanString::from_static_str("0123456789abcdefghijklmnopqrstuvwxyz");
+    assert_eq!(static_.remaining_capacity(), 0);
+
+    assert_eq!(LeanString::new().remaining_capacity(), INLINE_LIMIT);
+}
+
+#[test]
+fn push_str_bounded() {
+    // fits entirely
+    let mut s = LeanString::from("abc");
+    assert_eq!(s.push_str_bounded("def", 10), 3);
+    assert_eq!(s, "abcdef");
+
+    // the cap lands in the middle of '🦀' (4 bytes)
+    let mut s = LeanString::from("abc");
+    assert_eq!(s.push_str_bounded("x🦀y", 6), 1);
+    assert_eq!(s, "abcx");
+    assert_eq!(s.push_str_bounded("🦀", 8), 4);
+    assert_eq!(s, "abcx🦀");
+
+    // already over the cap
+    let mut s = LeanString::from("0123456789");
+    assert_eq!(s.push_str_bounded("abc", 5), 0);
+    assert_eq!(s, "0123456789");
+
+    // grows into the heap
+    let mut s = LeanString::new();
+    let text = "こんにちは、世界! Hello, world!";
+    assert_eq!(s.push_str_bounded(text, 20), 18);
+    assert_eq!(s, "こんにちは、");
+    assert_eq!(s.push_str_bounded(text, usize::MAX), text.len());
+}
+
+#[test]
+fn cmp_ignore_ascii_case() {
+    use core::cmp::Ordering;
+
+    let mut names: Vec<LeanString> = ["banana", "Apple", "cherry", "apple", "BANANA"]
+        .into_iter()
+        .map(LeanString::from)
+        .collect();
+    names.sort_by(|a, b| a.cmp_ignore_ascii_case(b));
+    // stable sort keeps the original order of equal (case-folded) names
+    assert_eq!(names, ["Apple", "apple", "banana", "BANANA", "cherry"]);
+
+    let s = LeanString::from("Straße");
+    assert_eq!(s.cmp_ignore_ascii_case("STRAßE"), Ordering::Equal);
+    // non-ASCII is not folded
+    assert_ne!(s.cmp_ignore_ascii_case("STRASSE"), Ordering::Equal);
+    assert_eq!(s.cmp_ignore_ascii_case("straß"), Ordering::Greater);
+    assert_eq!(LeanString::new().cmp_ignore_ascii_case(""), Ordering::Equal);
+}
+
+#[test]
+fn reserve_total() {
+    let mut s = LeanString::from("abc");
+    s.reserve_total(INLINE_LIMIT);
+    assert!(!s.is_heap_allocated());
+
+    s.reserve_total(100);
+    assert!(s.is_heap_allocated());
+    assert!(s.capacity() >= 100);
+
+    // no-op when the capacity already suffices
+    let cap = s.capacity();
+    let ptr = s.as_ptr();
+    s.reserve_total(10);
+    s.reserve_total(cap);
+    assert_eq!(s.capacity(), cap);
+    assert_eq!(s.as_ptr(), ptr);
+
+    // a static buffer is kept if enough
+    let text = "0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut static_ = LeanString::from_static_str(text);
+    static_.reserve_total(text.len());
+    assert_eq!(static_.as_ptr(), text.as_ptr());
+    static_.reserve_total(text.len() + 1);
+    assert!(static_.capacity() > text.len());
+    assert!(static_.is_heap_allocated());
+
+    assert!(LeanString::new().try_reserve_total(usize::MAX).is_err());
+}
+
+#[test]
+fn join_chars() {
+    assert_eq!(LeanString::join_chars(&[], ','), "");
+    assert!(!LeanString::join_chars(&[], ',').is_heap_allocated());
+
+    // no separator for a single element
+    let single = [LeanString::from("alone")];
+    assert_eq!(LeanString::join_chars(&single, ','), "alone");
+
+    let pieces: Vec<LeanString> = ["path", "to", "", "file"].into_iter().map(Into::into).collect();
+    assert_eq!(LeanString::join_chars(&pieces, '/'), "path/to//file");
+    assert_eq!(LeanString::join_chars(&pieces, '🦀'), "path🦀to🦀🦀file");
+
+    // allocated exactly once when the result goes to the heap
+    let pieces: Vec<LeanString> = (0..10).map(|i| LeanString::from(i.to_string())).collect();
+    let joined = LeanString::join_chars(&pieces, '\n');
+    assert_eq!(joined, "0\n1\n2\n3\n4\n5\n6\n7\n8\n9");
+    if joined.len() > INLINE_LIMIT {
+        assert_eq!(joined.capacity(), joined.len());
+    }
+}
+
+#[test]
+fn from_fmt() {
+    let (x, y) = (12, "abc");
+    let s = LeanString::from(format_args!("{x}-{y}"));
+    assert_eq!(s, format!("{x}-{y}"));
+    assert!(!s.is_heap_allocated());
+
+    let long = LeanString::from_fmt(format_args!("{x:>40}|{y:?}"));
+    assert_eq!(long, format!("{x:>40}|{y:?}"));
+    assert!(long.is_heap_allocated());
+
+    // a plain literal doesn't copy
+    let literal = LeanString::from_fmt(format_args!("0123456789abcdefghijklmnopqrstuvwxyz"));
+    assert_eq!(literal, "0123456789abcdefghijklmnopqrstuvwxyz");
+    assert!(!literal.is_heap_allocated());
+
+    struct Failing;
+    impl core::fmt::Display for Failing {
+        fn fmt(&self, _: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            Err(core::fmt::Error)
+        }
+    }
+    assert!(LeanString::try_from_fmt(format_args!("{}", Failing)).is_err());
+}
+
+#[test]
+fn char_range() {
+    let s = LeanString::from("a€🦀 and some more text");
+    assert!(s.is_char_boundary(0));
+    assert!(s.is_char_boundary(1));
+    assert!(!s.is_char_boundary(2));
+    assert!(s.is_char_boundary(s.len()));
+    assert!(!s.is_char_boundary(s.len() + 1));
+
+    assert_eq!(s.char_range(0..1), Some("a"));
+    assert_eq!(s.char_range(1..4), Some("€"));
+    assert_eq!(s.char_range(4..8), Some("🦀"));
+    assert_eq!(s.char_range(0..s.len()), Some(s.as_str()));
+    assert_eq!(s.char_range(4..4), Some(""));
+
+    // interior of a char
+    assert_eq!(s.char_range(0..2), None);
+    assert_eq!(s.char_range(2..4), None);
+    assert_eq!(s.char_range(5..8), None);
+    // out of bounds or reversed
+    assert_eq!(s.char_range(0..s.len() + 1), None);
+    #[allow(clippy::reversed_empty_ranges)]
+    let reversed = s.char_range(4..1);
+    assert_eq!(reversed, None);
+}
+
+#[test]
+fn push_bytes_checked() {
+    use lean_string::PushBytesError;
+
+    let mut s = LeanString::from("abc");
+    s.push_bytes_checked("🦀 ok".as_bytes()).unwrap();
+    assert_eq!(s, "abc🦀 ok");
+    s.push_bytes_checked(b"").unwrap();
+    assert_eq!(s, "abc🦀 ok");
+
+    // invalid in the middle, at the end (truncated char), and a lone continuation byte
+    for invalid in [&b"de\xFFf"[..], &"xyz🦀".as_bytes()[..6], b"\x80"] {
+        let before = s.clone();
+        let err = s.push_bytes_checked(invalid).unwrap_err();
+        assert!(matches!(err, PushBytesError::Utf8(_)));
+        assert_eq!(s, before);
+    }
+
+    // no partial append even if the valid prefix would move it to the heap
+    let mut s = LeanString::new();
+    let mut long = b"0123456789abcdefghijklmnopqrstuvwxyz".to_vec();
+    long.push(0xC0);
+    assert!(s.push_bytes_checked(&long).is_err());
+    assert_eq!(s, "");
+    assert!(!s.is_heap_allocated());
+}
+
+#[test]
+fn capacity_constants() {
+    const _: () = assert!(LeanString::INLINE_CAPACITY == INLINE_LIMIT);
+    #[cfg(all(target_pointer_width = "64", not(feature = "wide_inline")))]
+    const _: () = assert!(LeanString::INLINE_CAPACITY == 16);
+    #[cfg(all(target_pointer_width = "64", feature = "wide_inline"))]
+    const _: () = assert!(LeanString::INLINE_CAPACITY == 24);
+    #[cfg(target_pointer_width = "64")]
+    const _: () = assert!(LeanString::MAX_HEAP_CAPACITY == (1 << 56) - 1);
+    #[cfg(target_pointer_width = "32")]
+    const _: () = assert!(LeanString::MAX_HEAP_CAPACITY == (1 << 24) - 2);
+
+    assert_eq!(LeanString::new().capacity(), LeanString::INLINE_CAPACITY);
+    let s = LeanString::with_capacity(LeanString::INLINE_CAPACITY + 1);
+    assert!(s.is_heap_allocated());
+    assert!(LeanString::try_with_capacity(LeanString::MAX_HEAP_CAPACITY + 1).is_err());
+}
+
+#[test]
+fn empty_constant() {
+    const _: () = {
+        let empty = LeanString::EMPTY;
+        assert!(empty.is_empty());
+        assert!(!empty.is_heap_allocated());
+        core::mem::forget(empty);
+    };
+    const _: () = {
+        let s = LeanString::from_static_str("a static string, longer than inline");
+        assert!(s.len() == 35);
+        assert!(!s.is_heap_allocated());
+        core::mem::forget(s);
+    };
+
+    assert_eq!(LeanString::EMPTY, LeanString::new());
+    assert_eq!(LeanString::EMPTY, "");
+    assert_eq!(LeanString::EMPTY.capacity(), INLINE_LIMIT);
+
+    // it hashes the same as an empty `str` does
+    use std::{collections::hash_map::RandomState, hash::BuildHasher};
+    let state = RandomState::new();
+    assert_eq!(state.hash_one(LeanString::EMPTY), state.hash_one(""));
+}
+
+#[test]
+fn with_saved_len() {
+    let mut s = LeanString::from("prefix/");
+    {
+        let mut scoped = s.with_saved_len();
+        assert_eq!(scoped.saved_len(), 7);
+        scoped.push_str("a long suffix which moves the string to the heap");
+        assert!(scoped.is_heap_allocated());
+    }
+    assert_eq!(s, "prefix/");
+    // the capacity is retained, so the next round doesn't reallocate
+    let cap = s.capacity();
+    let ptr = s.as_ptr();
+    for suffix in ["one", "two", "a suffix which is a bit longer"] {
+        let mut scoped = s.with_saved_len();
+        scoped.push_str(suffix);
+        assert_eq!(scoped.as_ptr(), ptr);
+        assert_eq!(scoped.len(), 7 + suffix.len());
+    }
+    assert_eq!(s, "prefix/");
+    assert_eq!(s.capacity(), cap);
+
+    // shared while the guard is alive
+    let cloned = {
+        let mut scoped = s.with_saved_len();
+        scoped.push_str("shared");
+        scoped.clone()
+    };
+    assert_eq!(s, "prefix/");
+    assert_eq!(cloned, "prefix/shared");
+
+    // shortened below the saved length or not on a char boundary: left as it is
+    {
+        let mut scoped = s.with_saved_len();
+        scoped.clear();
+        scoped.push_str("abc");
+    }
+    assert_eq!(s, "abc");
+    {
+        let mut scoped = s.with_saved_len();
+        scoped.clear();
+        scoped.push_str("éé");
+    }
+    assert_eq!(s, "éé");
+}
+
+#[test]
+fn into_static_str() {
+    let text: &'static str = "0123456789abcdefghijklmnopqrstuvwxyz";
+    let s = LeanString::from_static_str(text);
+    assert_eq!(s.into_static_str().as_ptr(), text.as_ptr());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // leaks memory intentionally
+fn into_static_str_leaks_copy() {
+    let text: &'static str = "0123456789abcdefghijklmnopqrstuvwxyz";
+
+    // a modified static buffer is copied
+    let mut s = LeanString::from_static_str(text);
+    s.push('!');
+    let leaked = s.into_static_str();
+    assert_eq!(leaked, "0123456789abcdefghijklmnopqrstuvwxyz!");
+
+    // a shared heap buffer keeps the other reference valid
+    let heap = LeanString::from(text);
+    let leaked = heap.clone().into_static_str();
+    assert_eq!(leaked, text);
+    assert_ne!(leaked.as_ptr(), heap.as_ptr());
+    assert_eq!(heap, text);
+
+    assert_eq!(LeanString::new().into_static_str(), "");
+    assert_eq!(LeanString::from("short").try_into_static_str(), Ok("short"));
+}
+
+#[test]
+fn add_lean_string() {
+    let a = LeanString::from("foo");
+    let b = LeanString::from("bar");
+    assert_eq!(a + b, "foobar");
+
+    assert_eq!(LeanString::new() + LeanString::from("bar"), "bar");
+    assert_eq!(LeanString::from("foo") + LeanString::new(), "foo");
+
+    // static rhs stays static when lhs is empty
+    let text: &'static str = "0123456789abcdefghijklmnopqrstuvwxyz";
+    let sum = LeanString::new() + LeanString::from_static_str(text);
+    assert_eq!(sum.as_ptr(), text.as_ptr());
+
+    // shared lhs is not modified
+    let lhs = LeanString::from(text);
+    let sum = lhs.clone() + LeanString::from("!");
+    assert_eq!(lhs, text);
+    assert_eq!(sum, "0123456789abcdefghijklmnopqrstuvwxyz!");
+}
+
+#[test]
+fn shallow_and_deep_clone() {
+    let text = "0123456789abcdefghijklmnopqrstuvwxyz";
+
+    // `clear` keeps the heap buffer only if it is unique, which tells the reference count.
+    let mut s = LeanString::from(text);
+    let shallow = s.shallow_clone();
+    assert_eq!(shallow.as_ptr(), s.as_ptr());
+    s.clear();
+    assert_eq!(s.capacity(), INLINE_LIMIT);
+    assert_eq!(shallow, text);
+
+    let mut s = LeanString::from(text);
+    let mut deep = s.deep_clone().unwrap();
+    assert_eq!(deep, text);
+    assert_ne!(deep.as_ptr(), s.as_ptr());
+    s.clear();
+    assert_eq!(s.capacity(), text.len());
+    deep.clear();
+    assert_eq!(deep.capacity(), text.len());
+
+    // deep clone of a static buffer is not static anymore
+    let s = LeanString::from_static_str(text);
+    let deep = s.deep_clone().unwrap();
+    assert!(deep.is_heap_allocated());
+    assert_eq!(s.shallow_clone().as_ptr(), text.as_ptr());
+
+    let s = LeanString::from("inline");
+    assert_eq!(s.deep_clone().unwrap(), s.shallow_clone());
+}
+
+#[test]
+fn from_str_slices() {
+    assert_eq!(LeanString::from(&[][..] as &[&str]), "");
+    assert_eq!(LeanString::from(&["", ""][..]), "");
+
+    let short = LeanString::from(&["usr", "/", "bin"][..]);
+    assert_eq!(short, "usr/bin");
+    assert!(!short.is_heap_allocated());
+
+    // crosses the inline limit, and is reserved exactly once
+    let parts = ["0123456789", "abcdefghijklmnopqrstuvwxyz", "!"];
+    let long = LeanString::from(&parts[..]);
+    assert_eq!(long, parts.concat());
+    assert!(long.is_heap_allocated());
+    assert_eq!(long.capacity(), long.len());
+}
+
+#[test]
+fn contains_ascii_byte() {
+    let text = "0123456789abcdefghijklmnopqrstuvwxyz";
+    // every length around the inline limit, and every position
+    for len in 0..text.len() {
+        let s = LeanString::from(&text[..len]);
+        for byte in text.bytes() {
+            assert_eq!(s.contains_ascii_byte(byte), text.as_bytes()[..len].contains(&byte));
+        }
+        assert!(!s.contains_ascii_byte(b'!'));
+        let s = LeanString::from_static_str(&text[..len]);
+        assert_eq!(s.contains_ascii_byte(b'z'), len == text.len());
+    }
+
+    // the length byte and the leftover bytes after truncation are not searched
+    let mut s = LeanString::from("abcdefgh");
+    s.pop();
+    assert!(!s.contains_ascii_byte(b'h'));
+    assert!(!LeanString::new().contains_ascii_byte(0));
+    assert!(!LeanString::from("abc").contains_ascii_byte(0xC3));
+
+    // NUL and bytes adjacent to a match (false positives of SWAR)
+    let s = LeanString::from("\0\x01\x7F");
+    assert!(s.contains_ascii_byte(0));
+    assert!(s.contains_ascii_byte(1));
+    assert!(s.contains_ascii_byte(0x7F));
+    assert!(!s.contains_ascii_byte(2));
+    assert!(!LeanString::from("ÿ").contains_ascii_byte(0x7F));
+}
+
+#[test]
+fn split_to_lean() {
+    for text in ["", ",", "a,b,,c", ",leading", "trailing,", "no separator", "🦀,ü,,"] {
+        let s = LeanString::from(text);
+        let lean: Vec<LeanString> = s.split_to_lean(',').collect();
+        let std: Vec<&str> = text.split(',').collect();
+        assert_eq!(lean, std);
+    }
+
+    let s = LeanString::from("short/0123456789abcdefghijklmnopqrstuvwxyz/x");
+    let parts: Vec<LeanString> = s.split_to_lean('/').collect();
+    assert_eq!(parts, ["short", "0123456789abcdefghijklmnopqrstuvwxyz", "x"]);
+    assert!(!parts[0].is_heap_allocated());
+    assert!(parts[1].is_heap_allocated());
+    assert!(!parts[2].is_heap_allocated());
+
+    let s = LeanString::from("α→β→γ");
+    assert_eq!(s.split_to_lean('→').collect::<Vec<_>>(), ["α", "β", "γ"]);
+}
+
+#[test]
+fn checked_insert_str() {
+    use lean_string::InsertError;
+
+    let mut s = LeanString::from("こんにちは");
+    assert_eq!(s.checked_insert_str(0, "「"), Ok(()));
+    assert_eq!(s.checked_insert_str(s.len(), "」"), Ok(()));
+    assert_eq!(s, "「こんにちは」");
+
+    assert_eq!(s.checked_insert_str(1, "x"), Err(InsertError::NotCharBoundary));
+    assert_eq!(s.checked_insert_str(s.len() - 1, "x"), Err(InsertError::NotCharBoundary));
+    assert_eq!(s.checked_insert_str(s.len() + 1, "x"), Err(InsertError::OutOfBounds));
+    assert_eq!(s.checked_insert_str(usize::MAX, ""), Err(InsertError::OutOfBounds));
+    assert_eq!(s, "「こんにちは」");
+}
+
+#[test]
+fn from_static_cow() {
+    use std::borrow::Cow;
+
+    let text: &'static str = "0123456789abcdefghijklmnopqrstuvwxyz";
+    let s = LeanString::from_static_cow(Cow::Borrowed(text));
+    assert_eq!(s.as_ptr(), text.as_ptr());
+    assert!(!s.is_heap_allocated());
+    // round trip
+    assert!(matches!(s.into_cow(), Cow::Borrowed(t) if t.as_ptr() == text.as_ptr()));
+
+    let s = LeanString::from_static_cow(Cow::Owned(text.to_string()));
+    assert_eq!(s, text);
+    assert!(s.is_heap_allocated());
+
+    assert_eq!(LeanString::from_static_cow(Cow::Borrowed("short")), "short");
+}
+
+#[test]
+fn write_char() {
+    use core::fmt::Write;
+
+    let mut written = LeanString::new();
+    let mut pushed = LeanString::new();
+    for ch in "a€🦀 こんにちは".chars().cycle().take(100) {
+        written.write_char(ch).unwrap();
+        pushed.push(ch);
+        assert_eq!(written, pushed);
+    }
+    assert!(written.is_heap_allocated());
+
+    // `write!` with `{}` of a char goes through `write_str`, so compare with it too
+    let mut formatted = LeanString::new();
+    for ch in pushed.chars() {
+        write!(formatted, "{ch}").unwrap();
+    }
+    assert_eq!(formatted, written);
+}
+
+#[test]
+fn with_capacity_like() {
+    let heap = LeanString::from("0123456789abcdefghijklmnopqrstuvwxyz");
+    let sibling = LeanString::with_capacity_like(&heap);
+    assert!(sibling.is_empty());
+    assert_eq!(sibling.capacity(), heap.capacity());
+    assert!(heap.is_heap_allocated());
+    assert!(sibling.is_heap_allocated());
+
+    let inline = LeanString::from("abc");
+    let sibling = LeanString::with_capacity_like(&inline);
+    assert_eq!(sibling.capacity(), INLINE_LIMIT);
+    assert!(!sibling.is_heap_allocated());
+}
+
+#[test]
+fn pop_n() {
+    let mut s = LeanString::from("a€🦀ü");
+    assert_eq!(s.pop_n(0), "");
+    assert_eq!(s, "a€🦀ü");
+    assert_eq!(s.pop_n(2), "🦀ü");
+    assert_eq!(s, "a€");
+    assert_eq!(s.pop_n(5), "a€");
+    assert_eq!(s, "");
+    assert_eq!(s.pop_n(1), "");
+
+    // shared buffer: the other one is untouched
+    let text = "0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut s = LeanString::from(text);
+    let other = s.clone();
+    assert_eq!(s.pop_n(26), "abcdefghijklmnopqrstuvwxyz");
+    assert_eq!(s, "0123456789");
+    assert_eq!(other, text);
+
+    // static buffer
+    let mut s = LeanString::from_static_str(text);
+    assert_eq!(s.pop_n(1), "z");
+    assert_eq!(s, &text[..35]);
+
+    // unique heap buffer is truncated in place
+    let mut s = LeanString::from(text);
+    let ptr = s.as_ptr();
+    let suffix = s.pop_n(3);
+    assert_eq!(suffix, "xyz");
+    assert_eq!(s.as_ptr(), ptr);
+    assert_eq!(s.capacity(), text.len());
+}
+
+#[test]
+fn with_pushed() {
+    let s = LeanString::new().with_pushed_str("a").with_pushed('b');
+    assert_eq!(s, "ab");
+    assert!(!s.is_heap_allocated());
+
+    let path = ["usr", "local", "share", "lean_string", "examples"]
+        .into_iter()
+        .fold(LeanString::new(), |acc, part| acc.with_pushed('/').with_pushed_str(part));
+    assert_eq!(path, "/usr/local/share/lean_string/examples");
+
+    // a shared buffer is copied, the origin is untouched
+    let origin = LeanString::from("0123456789abcdefghijklmnopqrstuvwxyz");
+    let extended = origin.clone().with_pushed('!');
+    assert_eq!(origin, "0123456789abcdefghijklmnopqrstuvwxyz");
+    assert_eq!(extended, "0123456789abcdefghijklmnopqrstuvwxyz!");
+}
+
+#[test]
+fn retain_filters() {
+    let mut s = LeanString::from("Héllo, wörld! 123 — 🦀 ok");
+    s.retain_ascii_alphanumeric();
+    assert_eq!(s, "Hllowrld123ok");
+
+    let mut s = LeanString::from("Héllo, wörld! 123 — 🦀 ok");
+    s.retain_matching(&['é', 'l', '🦀', ' ']);
+    assert_eq!(s, "éll l   🦀 ");
+
+    // multibyte chars outside the set are dropped
+    let mut s = LeanString::from("ααααααααααβββββββββββabc");
+    s.retain_matching(&['a', 'b', 'c']);
+    assert_eq!(s, "abc");
+
+    let mut s = LeanString::from("nothing matches");
+    s.retain_matching(&[]);
+    assert_eq!(s, "");
+}
+
+#[test]
+fn retain_indexed() {
+    // drop every other char by position
+    let mut s = LeanString::from("abcdefghijklmnopqrstuvwxyz");
+    s.retain_indexed(|idx, _| idx % 2 == 0);
+    assert_eq!(s, "acegikmoqsuwy");
+
+    // the positions are byte offsets in the original string, even across multibyte chars
+    let text = "aé🦀あzé🦀あz";
+    let mut indices = Vec::new();
+    let mut s = LeanString::from(text);
+    s.retain_indexed(|idx, ch| {
+        indices.push((idx, ch));
+        ch != 'é'
+    });
+    assert_eq!(s, "a🦀あz🦀あz");
+    assert_eq!(indices, text.char_indices().collect::<Vec<_>>());
+
+    // keep a byte range, which starts in the middle of a char
+    let mut s = LeanString::from(text);
+    s.retain_indexed(|idx, _| (2..11).contains(&idx));
+    assert_eq!(s, "🦀あz");
+
+    // a shared buffer is not affected
+    let original = LeanString::from(text);
+    let mut shared = original.clone();
+    shared.retain_indexed(|idx, _| idx < 3);
+    assert_eq!(shared, "aé");
+    assert_eq!(original, text);
+}
+
+#[test]
+fn debug_repr() {
+    let inline = LeanString::from("abc");
+    assert_eq!(format!("{inline:?}"), r#""abc""#);
+    assert_eq!(format!("{inline:#?}"), r#""abc""#);
+    let out = format!("{:#?}", inline.debug_repr());
+    assert!(out.starts_with("LeanString {"));
+    assert!(out.contains(r#"str: "abc""#));
+    assert!(out.contains("kind: Inline"));
+    assert!(out.contains("len: 3"));
+    assert!(out.contains(&format!("capacity: {INLINE_LIMIT}")));
+    assert!(!out.contains("ref_count"));
+
+    let text = "0123456789abcdefghijklmnopqrstuvwxyz";
+    let heap = LeanString::from(text);
+    assert_eq!(format!("{heap:?}"), format!("{text:?}"));
+    assert_eq!(format!("{heap:#?}"), format!("{text:#?}"));
+    assert!(format!("{:?}", heap.debug_repr()).contains("kind: Heap"));
+    assert!(format!("{:?}", heap.debug_repr()).contains("ref_count: 1"));
+    let shared = heap.clone();
+    assert!(format!("{:?}", shared.debug_repr()).contains("ref_count: 2"));
+    assert!(format!("{:?}", heap.debug_repr()).contains("len: 36"));
+
+    let static_ = LeanString::from_static_str(text);
+    let out = format!("{:#?}", static_.debug_repr());
+    assert!(out.contains("kind: Static"));
+    assert!(out.contains("capacity: 36"));
+    assert!(!out.contains("ref_count"));
+}
+
+#[test]
+#[cfg(feature = "internal-debug")]
+fn assert_invariants() {
+    let text: &'static str = "0123456789abcdefghijklmnopqrstuvwxyz";
+
+    LeanString::new().assert_invariants();
+    for len in 0..=text.len() {
+        LeanString::from(&text[..len]).assert_invariants();
+        LeanString::from_static_str(&text[..len]).assert_invariants();
+    }
+    // a full inline buffer ends with a multibyte char
+    LeanString::from("é".repeat(INLINE_LIMIT / 2)).assert_invariants();
+
+    let heap = LeanString::with_capacity(100);
+    heap.assert_invariants();
+    let shared = heap.clone();
+    shared.assert_invariants();
+
+    let mut s = LeanString::from_static_str(text);
+    s.pop();
+    s.assert_invariants();
+    s.push_str("🦀");
+    s.assert_invariants();
+    s.shrink_to_fit();
+    s.assert_invariants();
+}
+
+#[test]
+fn make_ascii_lowercase() {
+    let text = "HELLO, WORLD! @[`{ ÀÉÎ Grüße ❤ ZZZZZZZZZZZZZZZZZZZZZZZZZZZZZZZZ";
+    let expected = text.to_ascii_lowercase();
+
+    let mut s = LeanString::from_static_str(text);
+    s.make_ascii_lowercase();
+    assert_eq!(s, expected);
+    assert!(s.is_heap_allocated());
+
+    let original = LeanString::from(text);
+    let mut shared = original.clone();
+    shared.make_ascii_lowercase();
+    assert_eq!(shared, expected);
+    assert_eq!(original, text);
+
+    // `str::to_ascii_lowercase` is still reachable through `Deref`
+    let lowered: String = original.to_ascii_lowercase();
+    assert_eq!(lowered, expected);
+
+    // already lowercase, the buffer is not copied
+    let mut s = LeanString::from_static_str("already lowercase, even when it is long");
+    s.make_ascii_lowercase();
+    assert!(!s.is_heap_allocated());
+
+    let mut inline = LeanString::from("AbC");
+    inline.make_ascii_lowercase();
+    assert_eq!(inline, "abc");
+    assert!(!inline.is_heap_allocated());
+}
+
+#[test]
+fn reserve_for() {
+    let mut s = LeanString::from("abc");
+    s.reserve_for([10, 20, 70]);
+    assert!(s.capacity() >= 103);
+
+    assert!(s.try_reserve_for([usize::MAX, 1]).is_err());
+    assert_eq!(s, "abc");
+}
+
+#[test]
+fn from_borrowed_string_types() {
+    for text in ["", "abc", "0123456789abcdefghijklmnopqrstuvwxyz"] {
+        let expected = LeanString::from(text);
+
+        let owned = String::from(text);
+        let from_ref = LeanString::from(&owned);
+        assert_eq!(from_ref, expected);
+        assert_eq!(from_ref.is_heap_allocated(), expected.is_heap_allocated());
+
+        let mut owned = owned;
+        let from_mut = LeanString::from(owned.as_mut_str());
+        assert_eq!(from_mut, expected);
+        assert_eq!(from_mut.is_heap_allocated(), expected.is_heap_allocated());
+        // the source is only borrowed
+        assert_eq!(owned, text);
+    }
+}
+
+#[test]
+fn encode_utf16_and_len_utf16() {
+    assert_eq!(LeanString::new().len_utf16(), 0);
+    assert_eq!(LeanString::from("abc").len_utf16(), 3);
+
+    // astral-plane chars are encoded as surrogate pairs
+    let s = LeanString::from("🦀😀𝄞");
+    assert_eq!(s.len(), 12);
+    assert_eq!(s.len_utf16(), 6);
+    let utf16: Vec<u16> = s.encode_utf16().collect();
+    assert_eq!(utf16, [0xD83E, 0xDD80, 0xD83D, 0xDE00, 0xD834, 0xDD1E]);
+    assert_eq!(LeanString::from_utf16(&utf16).unwrap(), s);
+
+    let s = LeanString::from("a€🦀 long enough to live on the heap");
+    assert_eq!(s.len_utf16(), s.as_str().encode_utf16().count());
+    assert_eq!(s.len_utf16(), s.chars().count() + 1);
+}
+
+#[test]
+fn fit_within() {
+    let text = "0123456789abcdefghijklmnopqrstuvwxyz";
+
+    let mut s = LeanString::from(text);
+    assert!(s.fit_within(text.len()));
+    assert!(s.fit_within(100));
+    assert_eq!(s, text);
+
+    assert!(!s.fit_within(10));
+    assert_eq!(s, "0123456789");
+    assert!(!s.fit_within(0));
+    assert_eq!(s, "");
+
+    // never splits a char, "€" is 3 bytes
+    let mut s = LeanString::from("€€€€€€€€€€€€€€€€");
+    assert!(!s.fit_within(10));
+    assert_eq!(s, "€€€");
+
+    // a shared buffer is copied, the other one is unchanged
+    let original = LeanString::from(text);
+    let mut shared = original.clone();
+    assert!(!shared.fit_within(3));
+    assert_eq!(shared, "012");
+    assert_eq!(original, text);
+
+    let mut static_ = LeanString::from_static_str(text);
+    assert!(!static_.fit_within(4));
+    assert_eq!(static_, "0123");
+}
+
+#[test]
+fn eq_smart_pointer_strs() {
+    use std::{rc::Rc, sync::Arc};
+
+    for text in ["", "abc", "0123456789abcdefghijklmnopqrstuvwxyz"] {
+        let s = LeanString::from(text);
+
+        let boxed: Box<str> = text.into();
+        let rc: Rc<str> = text.into();
+        let arc: Arc<str> = text.into();
+        assert_eq!(s, boxed);
+        assert_eq!(boxed, s);
+        assert_eq!(s, rc);
+        assert_eq!(rc, s);
+        assert_eq!(s, arc);
+        assert_eq!(arc, s);
+
+        let other = format!("{text}!");
+        let boxed: Box<str> = other.as_str().into();
+        let rc: Rc<str> = other.as_str().into();
+        let arc: Arc<str> = other.as_str().into();
+        assert_ne!(s, boxed);
+        assert_ne!(boxed, s);
+        assert_ne!(s, rc);
+        assert_ne!(rc, s);
+        assert_ne!(s, arc);
+        assert_ne!(arc, s);
+    }
+}
+
+#[test]
+fn push_around_inline_limit() {
+    for ch in ['a', 'é', 'あ', '🦀'] {
+        let mut s = LeanString::new();
+        let mut expected = String::new();
+        while expected.len() <= INLINE_LIMIT * 2 {
+            s.push(ch);
+            expected.push(ch);
+            assert_eq!(s, expected);
+            assert_eq!(s.is_heap_allocated(), expected.len() > INLINE_LIMIT);
+        }
+    }
+
+    // fill the inline buffer exactly, and then one more byte
+    let text = "0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut s = LeanString::from(&text[..INLINE_LIMIT - 2]);
+    s.push_str("");
+    s.push_str(&text[INLINE_LIMIT - 2..INLINE_LIMIT]);
+    assert_eq!(s, text[..INLINE_LIMIT]);
+    assert!(!s.is_heap_allocated());
+    s.push_str("");
+    assert_eq!(s, text[..INLINE_LIMIT]);
+    s.push(text.as_bytes()[INLINE_LIMIT] as char);
+    assert_eq!(s, text[..INLINE_LIMIT + 1]);
+    assert!(s.is_heap_allocated());
+
+    // a static buffer is never pushed to in place
+    let mut s = LeanString::from_static_str("ab");
+    s.push('c');
+    assert_eq!(s, "abc");
+    let mut s = LeanString::from_static_str(text);
+    s.push('!');
+    assert_eq!(s, format!("{text}!"));
+}
+
+#[test]
+fn from_static_utf8() {
+    static TEXT: &str = "0123456789abcdefghijklmnopqrstuvwxyz";
+
+    let long = LeanString::from_static_utf8(TEXT.as_bytes()).unwrap();
+    assert_eq!(long, TEXT);
+    assert_eq!(long.as_ptr(), TEXT.as_ptr());
+    assert!(!long.is_heap_allocated());
+
+    // short inputs are inlined
+    let short = LeanString::from_static_utf8(&TEXT.as_bytes()[..3]).unwrap();
+    assert_eq!(short, "012");
+    assert_ne!(short.as_ptr(), TEXT.as_ptr());
+
+    assert!(LeanString::from_static_utf8(b"").unwrap().is_empty());
+    assert!(LeanString::from_static_utf8(b"\xF0\x90\x80 is a truncated char, long").is_err());
+}
+
+#[test]
+fn shrink_to_fit_around_inline_limit() {
+    let text = "0123456789abcdefghijklmnopqrstuvwxyz";
+
+    let mut exact = LeanString::with_capacity(100);
+    exact.push_str(&text[..INLINE_LIMIT]);
+    exact.shrink_to_fit();
+    assert!(!exact.is_heap_allocated());
+    assert_eq!(exact, text[..INLINE_LIMIT]);
+
+    let mut over = LeanString::with_capacity(100);
+    over.push_str(&text[..INLINE_LIMIT + 1]);
+    over.shrink_to_fit();
+    assert!(over.is_heap_allocated());
+    assert_eq!(over.capacity(), INLINE_LIMIT + 1);
+    assert_eq!(over, text[..INLINE_LIMIT + 1]);
+}
+
+#[test]
+fn cmp_natural() {
+    use std::cmp::Ordering::*;
+
+    let cmp = |a: &str, b: &str| LeanString::from(a).cmp_natural(b);
+
+    assert_eq!(cmp("file2", "file10"), Less);
+    assert_eq!(cmp("file10", "file2"), Greater);
+    assert_eq!(cmp("file10", "file10"), Equal);
+    assert_eq!(cmp("", ""), Equal);
+    assert_eq!(cmp("", "0"), Less);
+
+    // leading zeros don't change the value, and ties are broken by the plain order
+    assert_eq!(cmp("file002", "file10"), Less);
+    assert_eq!(cmp("v01", "v1"), Less);
+    assert_eq!(cmp("v1", "v01"), Greater);
+    assert_eq!(cmp("v01.2", "v1.10"), Less);
+    assert_eq!(cmp("000", "0"), Greater);
+
+    // numbers of different lengths, and numbers longer than any integer type
+    assert_eq!(cmp("x9y", "x10y"), Less);
+    assert_eq!(cmp("x123456789012345678901234567890", "x99"), Greater);
+    assert_eq!(cmp("x123456789012345678901234567890", "x123456789012345678901234567891"), Less);
+
+    // the rest of the string is compared as usual
+    assert_eq!(cmp("a2b", "a2c"), Less);
+    assert_eq!(cmp("a2", "a2b"), Less);
+    assert_eq!(cmp("a", "1"), Greater);
+    assert_eq!(cmp("é1", "é01"), Greater);
+    assert_eq!(cmp("🦀10", "🦀9"), Greater);
+
+    let mut names: Vec<LeanString> =
+        ["img12.png", "img10.png", "IMG2.png", "img2.png", "img1.png", "img02.png"]
+            .into_iter()
+            .map(LeanString::from)
+            .collect();
+    names.sort_by(|a, b| a.cmp_natural(b));
+    assert_eq!(names, ["IMG2.png", "img1.png", "img02.png", "img2.png", "img10.png", "img12.png"]);
+}
+
+#[test]
+fn split_once_owned() {
+    let s = LeanString::from("key: a value which is long enough to be on the heap");
+    let (key, value) = s.split_once_owned(": ").unwrap();
+    assert_eq!(key, "key");
+    assert!(!key.is_heap_allocated());
+    assert_eq!(value, "a value which is long enough to be on the heap");
+    assert!(value.is_heap_allocated());
+
+    assert_eq!(s.split_once_owned("missing"), None);
+    assert_eq!(LeanString::new().split_once_owned(","), None);
+
+    // empty parts
+    let s = LeanString::from(",");
+    assert_eq!(s.split_once_owned(","), Some((LeanString::new(), LeanString::new())));
+    let s = LeanString::from("abc");
+    assert_eq!(s.split_once_owned("abc"), Some((LeanString::new(), LeanString::new())));
+    assert_eq!(s.split_once_owned(""), Some((LeanString::new(), LeanString::from("abc"))));
+    assert_eq!(s.split_once_owned("c"), Some((LeanString::from("ab"), LeanString::new())));
+
+    // the parts of a static buffer borrow the same static str
+    let text: &'static str =
+        "0123456789abcdefghijklmnopqrstuvwxyz|0123456789abcdefghijklmnopqrstuvwxyz";
+    let s = LeanString::from_static_str(text);
+    let (prefix, suffix) = s.split_once_owned("|").unwrap();
+    assert_eq!(prefix.as_ptr(), text.as_ptr());
+    assert_eq!(suffix.as_ptr(), text[37..].as_ptr());
+    assert!(!prefix.is_heap_allocated() && !suffix.is_heap_allocated());
+}
+
+#[test]
+fn get_and_get_mut() {
+    use std::ops::Bound;
+
+    let s = LeanString::from("aé🦀z");
+    assert_eq!(s.get(..), Some("aé🦀z"));
+    assert_eq!(s.get(1..3), Some("é"));
+    assert_eq!(s.get(1..=2), Some("é"));
+    assert_eq!(s.get(3..), Some("🦀z"));
+    assert_eq!(s.get(..=6), Some("aé🦀"));
+    assert_eq!(s.get((Bound::Excluded(0), Bound::Excluded(3))), Some("é"));
+    assert_eq!(s.get(8..8), Some(""));
+
+    // boundary failures
+    assert_eq!(s.get(2..), None);
+    assert_eq!(s.get(..4), None);
+    assert_eq!(s.get(3..5), None);
+    assert_eq!(s.get(..9), None);
+    assert_eq!(s.get((Bound::Included(3), Bound::Excluded(1))), None);
+    assert_eq!(s.get(..=usize::MAX), None);
+    assert_eq!(s.get((Bound::Excluded(usize::MAX), Bound::Unbounded)), None);
+
+    // an invalid range doesn't copy a shared buffer
+    let original = LeanString::from("a heap allocated string, which is shared");
+    let mut shared = original.clone();
+    assert_eq!(shared.get_mut(100..), None);
+    assert_eq!(shared.as_ptr(), original.as_ptr());
+
+    // mutating a sub-slice copies the shared buffer first
+    shared.get_mut(2..6).unwrap().make_ascii_uppercase();
+    assert_eq!(shared, "a HEAP allocated string, which is shared");
+    assert_eq!(original, "a heap allocated string, which is shared");
+
+    let mut static_ = LeanString::from_static_str("a static string, longer than inline");
+    static_.get_mut(..1).unwrap().make_ascii_uppercase();
+    assert_eq!(static_, "A static string, longer than inline");
+
+    let mut inline = LeanString::from("aé");
+    assert_eq!(inline.get_mut(2..), None);
+    assert_eq!(inline.try_get_mut(1..).unwrap().map(|s| &*s), Some("é"));
+}
+
+#[test]
+fn capacity_overflow_is_an_error() {
+    let text = "a heap allocated string, longer than inline";
+    let mut s = LeanString::from(text);
+    assert!(s.try_reserve(usize::MAX).is_err());
+    assert!(s.try_reserve(LeanString::MAX_HEAP_CAPACITY).is_err());
+    assert!(s.try_insert_char_repeated(0, '🦀', usize::MAX / 2).is_err());
+    assert!(s.try_insert_char_repeated(0, 'a', LeanString::MAX_HEAP_CAPACITY).is_err());
+    assert_eq!(s, text);
+}
+
+// The max capacity is reachable only on 32-bit architecture.
+#[test]
+#[cfg(target_pointer_width = "32")]
+fn push_beyond_max_capacity() {
+    let max = LeanString::MAX_HEAP_CAPACITY;
+
+    let mut s = LeanString::with_capacity(max);
+    s.push_str(&"a".repeat(max - 1));
+    assert_eq!(s.len(), max - 1);
+
+    // 2 bytes exceed the max capacity by 1
+    assert!(s.try_push_str("ab").is_err());
+    assert!(s.try_push('é').is_err());
+    assert!(s.try_insert_str(0, "ab").is_err());
+    assert_eq!(s.len(), max - 1);
+
+    // but 1 byte still fits
+    s.push('a');
+    assert_eq!(s.len(), max);
+    assert!(s.try_push('a').is_err());
+    assert!(s.try_insert(0, 'a').is_err());
+    assert_eq!(s.len(), max);
+
+    // a shared buffer neither
+    let shared = s.clone();
+    assert!(s.try_push('a').is_err());
+    assert_eq!(s.as_ptr(), shared.as_ptr());
+}
@@ -0,0 +1,75 @@
+#![cfg(feature = "nightly")]
+#![feature(extend_one)]
+
+use lean_string::LeanString;
+
+#[test]
+fn extend_one_chars_matches_extend() {
+    let text = "aé🦀あz, long enough to be on the heap";
+
+    let mut one_by_one = LeanString::from("start: ");
+    Extend::<char>::extend_reserve(&mut one_by_one, text.len());
+    for ch in text.chars() {
+        one_by_one.extend_one(ch);
+    }
+
+    let mut extended = LeanString::from("start: ");
+    extended.extend(text.chars());
+
+    assert_eq!(one_by_one, extended);
+    assert!(one_by_one.capacity() >= one_by_one.len());
+}
+
+#[test]
+fn extend_one_strs_matches_extend() {
+    let pieces = ["lorem", "", "ipsum", "dolor", "🦀", "sit", "amet"];
+
+    let mut one_by_one = LeanString::new();
+    Extend::<&str>::extend_reserve(&mut one_by_one, pieces.len());
+    for piece in pieces {
+        one_by_one.extend_one(piece);
+    }
+
+    let mut extended = LeanString::new();
+    extended.extend(pieces);
+
+    assert_eq!(one_by_one, extended);
+    assert_eq!(one_by_one, pieces.concat());
+}
+
+#[test]
+fn std_adapters_drive_extend_one() {
+    // `Iterator::unzip` (and `Extend for (A, B)`) uses `extend_one` and `extend_reserve`.
+    let (chars, strs): (LeanString, LeanString) =
+        [('a', "x"), ('é', "yy"), ('🦀', "zzz")].into_iter().unzip();
+    assert_eq!(chars, "aé🦀");
+    assert_eq!(strs, "xyyzzz");
+}
+
+#[test]
+fn lean_string_as_pattern() {
+    let sep = LeanString::from(", ");
+    let haystack = "alpha, beta, gamma";
+
+    let parts: Vec<&str> = haystack.split(&sep).collect();
+    assert_eq!(parts, ["alpha", "beta", "gamma"]);
+    assert_eq!(haystack.find(&sep), Some(5));
+    assert_eq!(haystack.rfind(&sep), Some(11));
+    assert_eq!(haystack.matches(&sep).count(), 2);
+    assert_eq!(haystack.split_once(&sep), Some(("alpha", "beta, gamma")));
+    assert_eq!(haystack.replace(&sep, "|"), "alpha|beta|gamma");
+
+    let affix = LeanString::from("al");
+    assert!(haystack.starts_with(&affix));
+    assert_eq!(haystack.strip_prefix(&affix), Some("pha, beta, gamma"));
+    assert!(!haystack.ends_with(&affix));
+    assert_eq!(haystack.strip_suffix(&LeanString::from("ma")), Some("alpha, beta, gam"));
+
+    // a heap allocated needle works the same
+    let long = LeanString::from("a needle which is long enough to be on the heap");
+    let text = format!("before {long} after");
+    assert_eq!(text.split(&long).collect::<Vec<_>>(), ["before ", " after"]);
+
+    let missing = LeanString::from(";");
+    assert_eq!(haystack.split(&missing).collect::<Vec<_>>(), [haystack]);
+}
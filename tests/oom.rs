@@ -0,0 +1,187 @@
+// Tests for the out-of-memory situation, using a global allocator that can be made to fail.
+
+use lean_string::{FallibleWriter, LeanString};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    fmt::Write,
+};
+
+struct FailingAlloc;
+
+thread_local! {
+    static FAIL: Cell<bool> = const { Cell::new(false) };
+}
+
+unsafe impl GlobalAlloc for FailingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if FAIL.with(Cell::get) {
+            std::ptr::null_mut()
+        } else {
+            unsafe { System.alloc(layout) }
+        }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if FAIL.with(Cell::get) {
+            std::ptr::null_mut()
+        } else {
+            unsafe { System.realloc(ptr, layout, new_size) }
+        }
+    }
+}
+
+#[global_allocator]
+static ALLOC: FailingAlloc = FailingAlloc;
+
+/// Runs `f` while every allocation on the current thread fails.
+fn with_failing_alloc<R>(f: impl FnOnce() -> R) -> R {
+    FAIL.with(|fail| fail.set(true));
+    let ret = f();
+    FAIL.with(|fail| fail.set(false));
+    ret
+}
+
+#[test]
+fn fallible_writer_success() {
+    let mut s = LeanString::new();
+    let mut writer = FallibleWriter::new(&mut s);
+    let text = "heap allocated string";
+    write!(writer, "{text}-{}", 42).unwrap();
+    assert!(writer.into_result().is_ok());
+    assert_eq!(s, "heap allocated string-42");
+}
+
+#[test]
+fn fallible_writer_captures_error() {
+    let mut s = LeanString::from("short");
+    let result = with_failing_alloc(|| {
+        let mut writer = FallibleWriter::new(&mut s);
+        let text = ", but this is too long to be inline";
+        let ret = write!(writer, "{text}");
+        assert!(ret.is_err());
+
+        // subsequent writes are rejected, even if they fit
+        assert!(write!(writer, "!").is_err());
+        writer.into_result()
+    });
+    assert!(result.is_err());
+    assert_eq!(s, "short");
+}
+
+const LONG: &str = "0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// Asserts that every failing operation leaves the (shared, static or unique heap) string as it
+/// was, with the same buffer.
+fn assert_unchanged_on_error(op: impl Fn(&mut LeanString) -> bool) {
+    // shared heap buffer
+    let mut s = LeanString::from(LONG);
+    let other = s.clone();
+    assert!(with_failing_alloc(|| op(&mut s)), "operation should fail on a shared buffer");
+    assert_eq!(s, LONG);
+    assert_eq!(s.as_ptr(), other.as_ptr());
+    // the reference count is intact: `s` is still valid (and unique) after `other` is dropped
+    drop(other);
+    assert_eq!(s, LONG);
+    s.push('!');
+    assert_eq!(s, "0123456789abcdefghijklmnopqrstuvwxyz!");
+
+    // static buffer
+    let mut s = LeanString::from_static_str(LONG);
+    assert!(with_failing_alloc(|| op(&mut s)), "operation should fail on a static buffer");
+    assert_eq!(s, LONG);
+    assert_eq!(s.as_ptr(), LONG.as_ptr());
+}
+
+#[test]
+fn insert_str_leaves_self_unchanged() {
+    assert_unchanged_on_error(|s| s.try_insert_str(3, "inserted").is_err());
+
+    // unique heap buffer without spare capacity
+    let mut s = LeanString::from(LONG);
+    let ptr = s.as_ptr();
+    assert!(with_failing_alloc(|| s.try_insert_str(3, "inserted")).is_err());
+    assert_eq!(s, LONG);
+    assert_eq!(s.as_ptr(), ptr);
+}
+
+#[test]
+fn push_str_leaves_self_unchanged() {
+    assert_unchanged_on_error(|s| s.try_push_str("pushed").is_err());
+    assert_unchanged_on_error(|s| s.try_push('!').is_err());
+    assert_unchanged_on_error(|s| s.try_reserve(1).is_err());
+
+    // unique heap buffer without spare capacity
+    let mut s = LeanString::from(LONG);
+    let ptr = s.as_ptr();
+    assert!(with_failing_alloc(|| s.try_push_str("pushed")).is_err());
+    assert_eq!(s, LONG);
+    assert_eq!(s.as_ptr(), ptr);
+
+    // inline buffer growing into the heap
+    let mut s = LeanString::from("short");
+    assert!(with_failing_alloc(|| s.try_push_str(LONG)).is_err());
+    assert_eq!(s, "short");
+    assert!(!s.is_heap_allocated());
+}
+
+#[test]
+fn retain_leaves_self_unchanged() {
+    assert_unchanged_on_error(|s| s.try_retain(|ch| ch.is_ascii_digit()).is_err());
+    assert_unchanged_on_error(|s| s.try_dedup_chars().is_err());
+}
+
+#[test]
+fn remove_leaves_self_unchanged() {
+    assert_unchanged_on_error(|s| s.try_remove(0).is_err());
+}
+
+#[test]
+fn pop_leaves_shared_self_unchanged() {
+    // a static buffer is shortened in place, so only a shared heap buffer can fail
+    let mut s = LeanString::from(LONG);
+    let other = s.clone();
+    assert!(with_failing_alloc(|| s.try_pop()).is_err());
+    assert_eq!(s.as_ptr(), other.as_ptr());
+    drop(other);
+    assert_eq!(s.pop(), Some('z'));
+}
+
+#[test]
+fn checked_insert_str_reports_reserve_error() {
+    use lean_string::{InsertError, ReserveError};
+
+    let mut s = LeanString::from("short");
+    let result = with_failing_alloc(|| s.checked_insert_str(0, LONG));
+    assert_eq!(result, Err(InsertError::Reserve(ReserveError)));
+    assert_eq!(s, "short");
+}
+
+#[test]
+fn write_char_returns_fmt_error() {
+    let mut s = LeanString::from(LONG);
+    let other = s.clone();
+    let result = with_failing_alloc(|| s.write_char('!'));
+    assert!(result.is_err());
+    assert_eq!(s.as_ptr(), other.as_ptr());
+
+    let mut writer = FallibleWriter::new(&mut s);
+    assert!(with_failing_alloc(|| writer.write_char('!')).is_err());
+    assert!(writer.write_char('!').is_err());
+    assert!(writer.into_result().is_err());
+    assert_eq!(s, LONG);
+}
+
+#[test]
+fn push_f64_restores_on_error() {
+    // The float is written in several parts, but it doesn't fit inline as a whole.
+    let prefix = "x".repeat(size_of::<LeanString>() - 3);
+    let mut s = LeanString::from(prefix.as_str());
+    let result = with_failing_alloc(|| s.try_push_f64(1.5, 30));
+    assert!(result.is_err());
+    assert_eq!(s, prefix);
+}
@@ -1,16 +1,25 @@
 use lean_string::{LeanString, ToLeanString};
 use proptest::{prelude::*, property_test};
 
+/// Checks the internal invariants of `s` if the `internal-debug` feature is enabled.
+fn check_invariants(s: &LeanString) {
+    #[cfg(feature = "internal-debug")]
+    s.assert_invariants();
+    #[cfg(not(feature = "internal-debug"))]
+    let _ = s;
+}
+
 #[property_test]
 #[cfg_attr(miri, ignore)]
 fn create_from_str(input: String) {
     let str = input.as_str();
 
     let lean = LeanString::from(str);
+    check_invariants(&lean);
     prop_assert_eq!(&lean, str);
     prop_assert_eq!(lean.len(), str.len());
 
-    if str.len() <= 2 * size_of::<usize>() {
+    if str.len() <= size_of::<LeanString>() {
         prop_assert!(!lean.is_heap_allocated());
     } else {
         prop_assert!(lean.is_heap_allocated());
@@ -32,6 +41,13 @@ fn create_from_u8_bytes(input: Vec<u8>) {
     let lean = LeanString::from_utf8_lossy(bytes);
     let string = String::from_utf8_lossy(bytes);
     prop_assert_eq!(&lean, &string);
+
+    let lean = LeanString::from_utf8_slice_copy(bytes);
+    let str = core::str::from_utf8(bytes);
+    prop_assert_eq!(lean.is_err(), str.is_err());
+    if let (Ok(lean), Ok(str)) = (lean, str) {
+        prop_assert_eq!(lean, str);
+    }
 }
 
 #[property_test]
@@ -55,6 +71,7 @@ fn create_from_u16_bytes(input: Vec<u16>) {
 #[cfg_attr(miri, ignore)]
 fn collect_from_chars(input: String) {
     let lean = input.chars().collect::<LeanString>();
+    check_invariants(&lean);
     prop_assert_eq!(&lean, &input);
 }
 
@@ -62,6 +79,7 @@ fn collect_from_chars(input: String) {
 #[cfg_attr(miri, ignore)]
 fn collect_from_strings(input: Vec<String>) {
     let lean = input.clone().into_iter().collect::<LeanString>();
+    check_invariants(&lean);
     let string = input.into_iter().collect::<String>();
     prop_assert_eq!(&lean, &string);
 }
@@ -100,6 +118,25 @@ fn f64_to_lean_string(f: f64) {
     prop_assert_eq!(f, float);
 }
 
+#[property_test]
+#[cfg_attr(miri, ignore)]
+fn push_int_and_uint(prefix: String, i: i64, u: u64) {
+    let mut lean = LeanString::from(prefix.as_str());
+    lean.push_int(i);
+    lean.push_uint(u);
+    prop_assert_eq!(lean, format!("{prefix}{i}{u}"));
+}
+
+#[property_test]
+#[cfg_attr(miri, ignore)]
+fn push_f64_matches_fmt(bits: u64, #[strategy = 0usize..40] precision: usize) {
+    let value = f64::from_bits(bits);
+    let mut lean = LeanString::new();
+    lean.push_f64(value, precision);
+    check_invariants(&lean);
+    prop_assert_eq!(lean, format!("{value:.precision$}"));
+}
+
 #[test]
 fn bool_to_lean_string() {
     let t = true;
@@ -113,3 +150,190 @@ fn bool_to_lean_string() {
 fn char_to_lean_string(c: char) {
     prop_assert_eq!(c.to_lean_string(), c.to_string());
 }
+
+#[property_test]
+#[cfg_attr(miri, ignore)]
+fn str_to_lean_string(s: String) {
+    let str = s.as_str();
+    prop_assert_eq!(str.to_lean_string(), LeanString::from(str));
+    prop_assert_eq!(
+        str.to_lean_string().is_heap_allocated(),
+        LeanString::from(str).is_heap_allocated()
+    );
+}
+
+#[property_test]
+#[cfg_attr(miri, ignore)]
+fn create_from_mostly_ascii_bytes(
+    #[strategy = proptest::collection::vec(0u8..0x80, 0..1024)] mut input: Vec<u8>,
+    noise: Vec<(usize, u8)>,
+) {
+    // Long ASCII inputs with a few random bytes hit the ASCII fast path of the `simd` feature.
+    if !input.is_empty() {
+        let len = input.len();
+        for (idx, byte) in noise.into_iter().take(4) {
+            input[idx % len] = byte;
+        }
+    }
+
+    let lean = LeanString::from_utf8(&input).map(String::from);
+    let string = core::str::from_utf8(&input).map(String::from);
+    prop_assert_eq!(lean, string);
+}
+
+#[property_test]
+#[cfg_attr(miri, ignore)]
+fn replace_and_count_matches(
+    #[strategy = "[abあ]{0,200}"] haystack: String,
+    #[strategy = "[abあ]{0,3}"] from: String,
+    #[strategy = "[xyあ]{0,3}"] to: String,
+) {
+    // A small alphabet makes many matches, and long haystacks hit the `memchr` fast path.
+    let lean = LeanString::from(haystack.as_str());
+
+    let replaced = lean.replace_lean(&from, &to);
+    check_invariants(&replaced);
+    prop_assert_eq!(replaced.capacity(), replaced.len().max(size_of::<LeanString>()));
+    prop_assert_eq!(replaced, haystack.replace(&from, &to));
+    for count in [0, 1, 3] {
+        prop_assert_eq!(
+            lean.replacen_lean(&from, &to, count),
+            haystack.replacen(&from, &to, count)
+        );
+    }
+    prop_assert_eq!(lean.count_matches(&from), haystack.matches(&from).count());
+    prop_assert_eq!(lean.contains_substr(&from), haystack.contains(&from));
+}
+
+#[property_test]
+#[cfg_attr(miri, ignore)]
+fn cmp_ignore_ascii_case(
+    #[strategy = "[a-dA-Dé]{0,20}"] a: String,
+    #[strategy = "[a-dA-Dé]{0,20}"] b: String,
+) {
+    let lean = LeanString::from(a.as_str());
+
+    let ordering = lean.cmp_ignore_ascii_case(&b);
+    prop_assert_eq!(ordering, a.to_ascii_lowercase().cmp(&b.to_ascii_lowercase()));
+    prop_assert_eq!(ordering.is_eq(), a.eq_ignore_ascii_case(&b));
+    prop_assert_eq!(ordering.reverse(), LeanString::from(b.as_str()).cmp_ignore_ascii_case(&a));
+}
+
+#[property_test]
+#[cfg_attr(miri, ignore)]
+fn make_ascii_lowercase(#[strategy = "[?-\\[_-{\x7F-\u{100}é❤]{0,80}"] s: String) {
+    let mut lean = LeanString::from(s.as_str());
+    let expected = s.to_ascii_lowercase();
+    lean.make_ascii_lowercase();
+    check_invariants(&lean);
+    prop_assert_eq!(lean, expected);
+}
+
+#[property_test]
+#[cfg_attr(miri, ignore)]
+fn swap_ascii_case(#[strategy = "[?-\\[_-{\x7F-\u{100}é❤]{0,80}"] s: String) {
+    let expected: String = s
+        .chars()
+        .map(|c| match c {
+            'a'..='z' => c.to_ascii_uppercase(),
+            'A'..='Z' => c.to_ascii_lowercase(),
+            _ => c,
+        })
+        .collect();
+    let mut lean = LeanString::from(s.as_str());
+    lean.swap_ascii_case();
+    prop_assert_eq!(lean, expected);
+}
+
+#[property_test]
+#[cfg_attr(miri, ignore)]
+fn reverse(s: String) {
+    let mut lean = LeanString::from(s.as_str());
+    lean.reverse();
+    prop_assert_eq!(lean, s.chars().rev().collect::<String>());
+}
+
+#[derive(Debug, Clone)]
+enum Op {
+    PushStr(String),
+    Push(char),
+    Pop,
+    Insert(usize, String),
+    Remove(usize),
+    Retain(char),
+    PopN(usize),
+    Clone,
+    ShrinkToFit,
+}
+
+fn op_strategy() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        "[a-zé🦀]{0,20}".prop_map(Op::PushStr),
+        any::<char>().prop_map(Op::Push),
+        Just(Op::Pop),
+        (any::<usize>(), "[a-zé🦀]{0,20}").prop_map(|(i, s)| Op::Insert(i, s)),
+        any::<usize>().prop_map(Op::Remove),
+        any::<char>().prop_map(Op::Retain),
+        (0usize..8).prop_map(Op::PopN),
+        Just(Op::Clone),
+        Just(Op::ShrinkToFit),
+    ]
+}
+
+#[property_test]
+#[cfg_attr(miri, ignore)]
+fn mutations_keep_invariants(
+    #[strategy = "[a-zé🦀]{0,40}"] init: String,
+    #[strategy = proptest::collection::vec(op_strategy(), 0..32)] ops: Vec<Op>,
+    is_static: bool,
+) {
+    let mut lean = if is_static {
+        LeanString::from_static_str(String::leak(init.clone()))
+    } else {
+        LeanString::from(init.as_str())
+    };
+    let mut string = init;
+    // keeps the buffer shared to exercise the copy-on-write paths
+    let mut shared = LeanString::new();
+
+    for op in ops {
+        match op {
+            Op::PushStr(s) => {
+                lean.push_str(&s);
+                string.push_str(&s);
+            }
+            Op::Push(ch) => {
+                lean.push(ch);
+                string.push(ch);
+            }
+            Op::Pop => prop_assert_eq!(lean.pop(), string.pop()),
+            Op::Insert(idx, s) => {
+                let idx = lean.floor_char_boundary(idx % (string.len() + 1));
+                lean.insert_str(idx, &s);
+                string.insert_str(idx, &s);
+            }
+            Op::Remove(idx) if !string.is_empty() => {
+                let idx = lean.floor_char_boundary(idx % string.len());
+                prop_assert_eq!(lean.remove(idx), string.remove(idx));
+            }
+            Op::Remove(_) => {}
+            Op::Retain(ch) => {
+                lean.retain(|c| c != ch);
+                string.retain(|c| c != ch);
+            }
+            Op::PopN(n) => {
+                let suffix = lean.pop_n(n);
+                check_invariants(&suffix);
+                let start =
+                    string.char_indices().rev().nth(n.wrapping_sub(1)).map_or(0, |(i, _)| i);
+                let start = if n == 0 { string.len() } else { start };
+                prop_assert_eq!(suffix, string.split_off(start));
+            }
+            Op::Clone => shared = lean.clone(),
+            Op::ShrinkToFit => lean.shrink_to_fit(),
+        }
+        check_invariants(&lean);
+        check_invariants(&shared);
+        prop_assert_eq!(&lean, &string);
+    }
+}
@@ -74,3 +74,34 @@ fn proptest_roundtrip(name: String, phones: Vec<String>, address: Option<String>
     assert_eq!(std_de_compact, std);
     assert_eq!(compact_de_std, compact);
 }
+
+fn leak(json: &str) -> &'static str {
+    String::from(json).leak()
+}
+
+#[test]
+fn deserialize_static() {
+    let from_json = |json: &'static str| {
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        LeanString::deserialize_static(&mut deserializer)
+    };
+
+    // long strings borrow the leaked buffer
+    let json = leak(r#""a long string which is borrowed from the JSON""#);
+    let borrowed = from_json(json).unwrap();
+    assert_eq!(borrowed, "a long string which is borrowed from the JSON");
+    assert!(!borrowed.is_heap_allocated());
+    assert_eq!(borrowed.as_ptr(), json[1..].as_ptr());
+
+    // short strings are inlined
+    let short = from_json(leak(r#""short""#)).unwrap();
+    assert_eq!(short, "short");
+    assert!(!short.is_heap_allocated());
+
+    // escaped strings can't be borrowed
+    let escaped = from_json(leak(r#""a long \"string\" which has to be copied""#)).unwrap();
+    assert_eq!(escaped, r#"a long "string" which has to be copied"#);
+    assert!(escaped.is_heap_allocated());
+
+    assert!(from_json("42").is_err());
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "unicode-segmentation")]
+
+use lean_string::LeanString;
+
+#[test]
+fn truncate_graphemes_keeps_flags() {
+    // Each flag is 2 regional indicator symbols (8 bytes).
+    let mut s = LeanString::from("🇯🇵🇺🇸🇫🇷");
+    s.truncate_graphemes(2);
+    assert_eq!(s, "🇯🇵🇺🇸");
+    assert_eq!(s.len(), 16);
+}
+
+#[test]
+fn truncate_graphemes_keeps_zwj_sequences() {
+    // 👨‍👩‍👧‍👦 is 7 chars joined by ZWJ, 1 grapheme cluster
+    let family = "👨\u{200D}👩\u{200D}👧\u{200D}👦";
+    let text = format!("{family}{family}!");
+
+    let mut s = LeanString::from(text.as_str());
+    let cloned = s.clone();
+    s.truncate_graphemes(1);
+    assert_eq!(s, family);
+    assert_eq!(cloned, text);
+
+    let mut s = LeanString::from(text.as_str());
+    s.truncate_graphemes(0);
+    assert_eq!(s, "");
+}
+
+#[test]
+fn truncate_graphemes_no_op() {
+    let mut s = LeanString::from_static_str("👍🏽 thumbs up with a skin tone modifier");
+    let len = s.len();
+    s.truncate_graphemes(100);
+    assert_eq!(s.len(), len);
+
+    s.truncate_graphemes(1);
+    assert_eq!(s, "👍🏽");
+}
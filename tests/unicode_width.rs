@@ -0,0 +1,29 @@
+#![cfg(feature = "unicode-width")]
+
+use lean_string::LeanString;
+
+#[test]
+fn display_width_of_full_width_chars() {
+    // Each CJK ideograph and fullwidth form is 2 columns.
+    assert_eq!(LeanString::from("漢字").display_width(), 4);
+    assert_eq!(LeanString::from("ｆｕｌｌ").display_width(), 8);
+    assert_eq!(LeanString::from("aあb").display_width(), 4);
+
+    let long = LeanString::from("こんにちは、世界。heap allocated");
+    assert_eq!(long.display_width(), 18 + 14);
+}
+
+#[test]
+fn display_width_of_combining_marks() {
+    assert_eq!(LeanString::new().display_width(), 0);
+
+    // U+0301 (combining acute accent) and U+200B (zero width space) are 0 columns.
+    assert_eq!(LeanString::from("\u{301}").display_width(), 0);
+    assert_eq!(LeanString::from("e\u{301}").display_width(), 1);
+    assert_eq!(LeanString::from("a\u{200B}b").display_width(), 2);
+
+    // the number of chars and bytes differ from the width
+    let s = LeanString::from("re\u{301}sume\u{301}");
+    assert_eq!(s.chars().count(), 8);
+    assert_eq!(s.display_width(), 6);
+}